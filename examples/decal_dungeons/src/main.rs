@@ -14,52 +14,9 @@ fn main() {
 #[folder = "$CARGO_MANIFEST_DIR/tiles"]
 struct Assets;
 
-#[derive(Debug, Clone, Copy)]
-struct Vec3d {
-    x: f32,
-    y: f32,
-    z: f32,
-}
-
-impl Vec3d {
-    const fn new(x: f32, y: f32, z: f32) -> Self {
-        Self { x, y, z }
-    }
-}
-impl std::ops::Mul<f32> for Vec3d {
-    type Output = Self;
-    fn mul(self, rhs: f32) -> Self::Output {
-        Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
-            z: self.z * rhs,
-        }
-    }
-}
-impl std::ops::Add<Self> for Vec3d {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.y,
-        }
-    }
-}
-impl std::ops::Sub<Self> for Vec3d {
-    type Output = Self;
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-            z: self.z - rhs.y,
-        }
-    }
-}
-
 #[derive(Debug, Clone, Copy)]
 struct Cube {
-    vertex: [Vec3d; 8],
+    vertex: [Vf3d; 8],
 }
 
 /*
@@ -77,14 +34,14 @@ impl Cube {
     #[rustfmt::skip]
     const UNIT: Cube = Cube {
         vertex: [
-            Vec3d::new(0.0, 0.0, 0.0),
-            Vec3d::new(1.0, 0.0, 0.0),
-            Vec3d::new(1.0,-1.0, 0.0),
-            Vec3d::new(0.0,-1.0, 0.0),
-            Vec3d::new(0.0, 0.0, 1.0),
-            Vec3d::new(1.0, 0.0, 1.0),
-            Vec3d::new(1.0,-1.0, 1.0),
-            Vec3d::new(0.0,-1.0, 1.0),
+            Vf3d::new(0.0, 0.0, 0.0),
+            Vf3d::new(1.0, 0.0, 0.0),
+            Vf3d::new(1.0,-1.0, 0.0),
+            Vf3d::new(0.0,-1.0, 0.0),
+            Vf3d::new(0.0, 0.0, 1.0),
+            Vf3d::new(1.0, 0.0, 1.0),
+            Vf3d::new(1.0,-1.0, 1.0),
+            Vf3d::new(0.0,-1.0, 1.0),
         ],
     };
 
@@ -94,7 +51,7 @@ impl Cube {
         }
     }
 
-    fn displace(&self, direction: Vec3d) -> Self {
+    fn displace(&self, direction: Vf3d) -> Self {
         Self {
             vertex: self.vertex.map(|v| v + direction),
         }
@@ -103,7 +60,7 @@ impl Cube {
     fn rotate_x_axis(&self, angle: f32) -> Self {
         let (sin, cos) = angle.sin_cos();
         Self {
-            vertex: self.vertex.map(|v| Vec3d {
+            vertex: self.vertex.map(|v| Vf3d {
                 x: v.x * cos + v.z * sin,
                 y: v.y,
                 z: v.x * sin + v.z * cos,
@@ -113,7 +70,7 @@ impl Cube {
     fn rotate_y_axis(&self, angle: f32) -> Self {
         let (sin, cos) = angle.sin_cos();
         Self {
-            vertex: self.vertex.map(|v| Vec3d {
+            vertex: self.vertex.map(|v| Vf3d {
                 x: v.x,
                 y: v.y * cos + v.z * sin,
                 z: v.y * sin + v.z * cos,
@@ -123,7 +80,7 @@ impl Cube {
 
     fn project(&self, dimention: Vf2d) -> Self {
         Self {
-            vertex: self.vertex.map(|v| Vec3d {
+            vertex: self.vertex.map(|v| Vf3d {
                 x: v.x + dimention.x * 0.5,
                 y: v.y + dimention.y * 0.5,
                 z: v.z,
@@ -136,12 +93,12 @@ impl Cube {
         angle: f32,
         pitch: f32,
         scale: f32,
-        camera: Vec3d,
+        camera: Vf3d,
         dimention: Vf2d,
     ) -> Self {
         Cube::UNIT
             .scale(scale)
-            .displace(Vec3d {
+            .displace(Vf3d {
                 x: cell.x * scale - camera.x,
                 y: -camera.y,
                 z: cell.y * scale - camera.z,
@@ -183,7 +140,7 @@ enum Sides {
 
 #[derive(Debug, Clone)]
 struct Quad {
-    points: [Vec3d; 4],
+    points: [Vf3d; 4],
     tile_id: Vi2d,
 }
 
@@ -226,7 +183,7 @@ fn create_face_quads(
     angle: f32,
     pitch: f32,
     scale: f32,
-    camera: Vec3d,
+    camera: Vf3d,
     dimention: Vf2d,
     visible_faces: &[bool; 6],
     quads: &mut Vec<Quad>,
@@ -294,7 +251,7 @@ async fn game() {
     let tile_size: Vf2d = Vf2d { x: 32.0, y: 32.0 };
     //drop(tilesheet_spr);
 
-    let mut camera_pos = Vec3d::new(0.0, 10.0, 0.0);
+    let mut camera_pos = Vf3d::new(0.0, 10.0, 0.0);
     let mut camera_angle = 2.0;
     let mut camera_pitch = 1.5;
     let mut camera_zoom = 16.0;
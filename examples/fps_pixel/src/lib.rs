@@ -1,5 +1,6 @@
 extern crate pixel_engine as engine;
 use engine::inputs::Keycodes as Keycode;
+use engine::inputs::{CursorGrab, MouseBtn};
 use engine::traits::*;
 use engine::*;
 use std::f64::consts::PI;
@@ -11,6 +12,9 @@ extern crate wasm_bindgen;
 use wasm_bindgen::prelude::*;
 
 pub mod maps;
+
+/// Radians turned per unit of mouse motion
+const MOUSE_SENSITIVITY: f64 = 0.003;
 struct Player {
     angle: f64,
     x: f64,
@@ -53,7 +57,19 @@ async fn init() {
     );
 
     let fac = 5;
-    let game = EngineWrapper::new("Pixel FPS".to_owned(), (120 * fac, 60 * fac, 10 / fac)).await;
+    let mut game =
+        EngineWrapper::new("Pixel FPS".to_owned(), (120 * fac, 60 * fac, 10 / fac)).await;
+    game.actions_mut()
+        .bind("turn_left", Keycode::Q)
+        .bind("turn_left", Keycode::Left)
+        .bind("turn_right", Keycode::D)
+        .bind("turn_right", Keycode::Right)
+        .bind("forward", Keycode::Z)
+        .bind("forward", Keycode::Up)
+        .bind("backward", Keycode::S)
+        .bind("backward", Keycode::Down)
+        .bind("strafe_left", Keycode::A)
+        .bind("strafe_right", Keycode::E);
     // =======================
     let viewport = (game.size().x, 7 * game.size().y / 8);
     let mut player = Player::new();
@@ -78,15 +94,15 @@ async fn init() {
     game.run(move |game: &mut Engine| {
         game.clear(Color::BLACK);
         // WRITE YOUR CODE HERE
-        if game.get_key(Keycode::Q).held {
+        if game.action("turn_left").held {
             // TURN TO THE LEFT
             player.angle -= (player.speed * 0.75_f64) * game.elapsed;
         }
-        if game.get_key(Keycode::D).held {
+        if game.action("turn_right").held {
             // TURN TO THE RIGHT
             player.angle += (player.speed * 0.75_f64) * game.elapsed;
         }
-        if game.get_key(Keycode::Z).held {
+        if game.action("forward").held {
             // MOVE FORWARD
             player.x += player.angle.sin() * player.speed * game.elapsed;
             player.y += player.angle.cos() * player.speed * game.elapsed;
@@ -95,7 +111,7 @@ async fn init() {
                 player.y -= player.angle.cos() * player.speed * game.elapsed;
             }
         }
-        if game.get_key(Keycode::S).held {
+        if game.action("backward").held {
             // MOCE BACKWARD
             player.x -= player.angle.sin() * player.speed * game.elapsed;
             player.y -= player.angle.cos() * player.speed * game.elapsed;
@@ -104,7 +120,7 @@ async fn init() {
                 player.y += player.angle.cos() * player.speed * game.elapsed;
             }
         }
-        if game.get_key(Keycode::A).held {
+        if game.action("strafe_left").held {
             // MOVE LEFT
             player.x -= player.angle.cos() * player.speed * game.elapsed;
             player.y += player.angle.sin() * player.speed * game.elapsed;
@@ -113,7 +129,7 @@ async fn init() {
                 player.y -= player.angle.sin() * player.speed * game.elapsed;
             }
         }
-        if game.get_key(Keycode::E).held {
+        if game.action("strafe_right").held {
             // MOVE RIGHT
             player.x += player.angle.cos() * player.speed * game.elapsed;
             player.y -= player.angle.sin() * player.speed * game.elapsed;
@@ -122,8 +138,20 @@ async fn init() {
                 player.y += player.angle.sin() * player.speed * game.elapsed;
             }
         }
-        if game.get_key(Keycode::Escape).any() {
-            return Ok(false);
+        // Click to look around with the mouse, escape to get the cursor back
+        if game.get_mouse_btn(MouseBtn::Left).pressed && game.cursor_grab() == CursorGrab::None {
+            game.set_cursor_grab(CursorGrab::Locked);
+            game.set_cursor_visible(false);
+        }
+        if game.cursor_grab() != CursorGrab::None {
+            player.angle += f64::from(game.get_mouse_delta().x) * MOUSE_SENSITIVITY;
+        }
+        if game.get_key(Keycode::Escape).pressed {
+            if game.cursor_grab() == CursorGrab::None {
+                return Ok(false);
+            }
+            game.set_cursor_grab(CursorGrab::None);
+            game.set_cursor_visible(true);
         }
 
         for x in 0..=(viewport.0) {
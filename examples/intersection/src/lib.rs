@@ -1,5 +1,6 @@
 use pixel_engine::{traits::*, Color};
 use pixel_engine::{
+    raycast::GridRay,
     vector2::{Vf2d, Vi2d, Vu2d},
     EngineWrapper,
 };
@@ -47,54 +48,14 @@ pub async fn init() {
         player.x = player.x.clamp(0.0, map_size.x as f32);
         player.y = player.y.clamp(0.0, map_size.y as f32);
 
-        let ray_start = player;
-        let ray_dir = (mousecell - player).norm();
-        let ray_unit_step_size = Vf2d {
-            x: (1.0 + (ray_dir.y / ray_dir.x) * (ray_dir.y / ray_dir.x)).sqrt(),
-            y: (1.0 + (ray_dir.x / ray_dir.y) * (ray_dir.x / ray_dir.y)).sqrt(),
-        };
-        let mut map_check = ray_start.cast_i32();
-        let mut ray_length1d = Vf2d { x: 0.0, y: 0.0 };
-        let mut step = Vi2d { x: 0, y: 0 };
-
-        if ray_dir.x < 0.0 {
-            step.x = -1;
-            ray_length1d.x = (ray_start.x - (map_check.x as f32)) * ray_unit_step_size.x;
-        } else {
-            step.x = 1;
-            ray_length1d.x = ((map_check.x as f32 + 1.0) - ray_start.x) * ray_unit_step_size.x;
-        }
-
-        if ray_dir.y < 0.0 {
-            step.y = -1;
-            ray_length1d.y = (ray_start.y - (map_check.y as f32)) * ray_unit_step_size.y;
-        } else {
-            step.y = 1;
-            ray_length1d.y = ((map_check.y as f32 + 1.0) - ray_start.y) * ray_unit_step_size.y;
-        }
-
-        let mut tile_found = false;
-        let mut distance = 0.0;
-        while !tile_found && distance < MAX_DISTANCE {
-            if ray_length1d.x < ray_length1d.y {
-                map_check.x += step.x;
-                distance = ray_length1d.x;
-                ray_length1d.x += ray_unit_step_size.x;
-            } else {
-                map_check.y += step.y;
-                distance = ray_length1d.y;
-                ray_length1d.y += ray_unit_step_size.y;
-            }
-            // if (vMapCheck.x >= 0 && vMapCheck.x < vMapSize.x && vMapCheck.y >= 0 && vMapCheck.y < vMapSize.y)
-            if (0..map_size.x).contains(&map_check.x) && (0..map_size.y).contains(&map_check.y) && map[(map_check.y * map_size.x + map_check.x) as usize] == 1 {
-                tile_found = true;
-            }
-        }
-
-        let mut intersection: Option<Vf2d> = None;
-        if tile_found {
-            intersection = Some(ray_start + ray_dir * distance);
-        }
+        let ray = GridRay::new(player, mousecell - player, MAX_DISTANCE);
+        let intersection = ray
+            .cast(|cell| {
+                (0..map_size.x).contains(&cell.x)
+                    && (0..map_size.y).contains(&cell.y)
+                    && map[(cell.y * map_size.x + cell.x) as usize] == 1
+            })
+            .map(|hit| hit.point);
 
         game.clear(0.into());
 
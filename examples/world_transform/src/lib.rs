@@ -50,24 +50,35 @@ impl Transform {
         F1: FnOnce(&px::Engine) -> bool,
         F2: FnOnce(&px::Engine) -> bool,
     {
-        let mouse: Vf2d = game.get_mouse_location().cast_f32();
-
-        let mouse_before = self.screen_to_world(mouse.cast_i32());
-
         if func_plus(game) {
-            self.scale *= 1.01;
+            self.zoom(game, 1.01);
         }
         if func_minus(game) {
-            self.scale *= 0.99;
+            self.zoom(game, 0.99);
         }
+    }
 
-        let mouse_after = self.screen_to_world(mouse.cast_i32());
+    /// Multiply the scale by `factor` while keeping the point under the mouse in place
+    pub fn zoom(&mut self, game: &px::Engine, factor: f32) {
+        let mouse = game.get_mouse_location().cast_i32();
+
+        let mouse_before = self.screen_to_world(mouse);
+        self.scale *= factor;
+        let mouse_after = self.screen_to_world(mouse);
         self.offset += mouse_before - mouse_after;
 
         self.world_top_left = self.screen_to_world((0, 0).into());
         self.world_bottom_right = self.screen_to_world(game.size().cast_i32());
     }
 
+    /// Zoom in or out by `speed` for each line scrolled with the mouse wheel
+    pub fn handle_scroll_zoom(&mut self, game: &px::Engine, speed: f32) {
+        let scroll = game.get_mouse_scroll().y;
+        if scroll != 0.0 {
+            self.zoom(game, speed.powf(scroll));
+        }
+    }
+
     pub fn get_mouse_location(&self, game: &px::Engine) -> Vf2d {
         let mouse = game.get_mouse_location().cast_i32();
 
@@ -27,6 +27,7 @@ async fn init() {
             |game| game.get_key(px::inputs::Keycodes::A).any(),
             |game| game.get_key(px::inputs::Keycodes::E).any(),
         );
+        transform.handle_scroll_zoom(game, 1.1);
 
         for y in 0..=10 {
             let y = y as f32;
@@ -58,6 +58,8 @@
             freetype
             mold
             clang
+            # gamepads (gilrs)
+            udev
           ];
 
           LIB_PATH = lib.makeLibraryPath [
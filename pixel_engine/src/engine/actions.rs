@@ -0,0 +1,373 @@
+//! Named actions and axes bound to inputs
+//!
+//! Instead of asking for a key directly, the game asks for an action (`"jump"`) and the
+//! [`ActionMap`] of the engine says which keys, mouse buttons and gamepad inputs trigger it. The
+//! bindings can be changed while the game runs, for example with
+//! [`Engine::pressed_binding`] in a "press any key" menu, and with the `serde` feature the map
+//! can be saved to and loaded from a config file in any serde format.
+//!
+//! ```
+//! use pixel_engine::actions::{ActionMap, AxisBinding, Binding};
+//! use pixel_engine::inputs::{Keycodes, MouseBtn};
+//!
+//! let mut map = ActionMap::new();
+//! map.bind("jump", Binding::Key(Keycodes::Space));
+//! map.bind("jump", Binding::Mouse(MouseBtn::Right));
+//! map.bind_axis("move_x", AxisBinding::keys(Keycodes::A, Keycodes::D));
+//! map.bind_axis("move_x", AxisBinding::keys(Keycodes::Left, Keycodes::Right));
+//! assert_eq!(map.bindings("jump").len(), 2);
+//! ```
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "gamepad")]
+use super::gamepad::{GamepadAxis, GamepadButton, GamepadId};
+use super::inputs::{Input, Keycodes, MouseBtn};
+use crate::Engine;
+
+/// An input that can trigger an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Binding {
+    /// A key of the keyboard
+    Key(Keycodes),
+    /// A mouse button
+    Mouse(MouseBtn),
+    /// A button of the gamepads, see [`ActionMap::set_gamepad`]
+    #[cfg(feature = "gamepad")]
+    GamepadButton(GamepadButton),
+}
+
+/// The gamepad read by the gamepad bindings, `None` reads every connected gamepad
+#[cfg(feature = "gamepad")]
+type PadFilter = Option<GamepadId>;
+#[cfg(not(feature = "gamepad"))]
+type PadFilter = Option<std::convert::Infallible>;
+
+impl Binding {
+    /// Get the status of the input, gamepad buttons are read on every connected gamepad
+    #[must_use]
+    pub fn input(self, engine: &Engine) -> Input {
+        self.read(engine, PadFilter::default())
+    }
+
+    #[cfg_attr(not(feature = "gamepad"), allow(unused_variables))]
+    fn read(self, engine: &Engine, gamepad: PadFilter) -> Input {
+        match self {
+            Binding::Key(key) => engine.get_key(key),
+            Binding::Mouse(button) => engine.get_mouse_btn(button),
+            #[cfg(feature = "gamepad")]
+            Binding::GamepadButton(button) => match gamepad {
+                Some(id) => engine.get_gamepad_button(id, button),
+                None => combine(
+                    engine
+                        .gamepads()
+                        .map(|id| engine.get_gamepad_button(id, button)),
+                ),
+            },
+        }
+    }
+}
+
+impl From<Keycodes> for Binding {
+    fn from(key: Keycodes) -> Self {
+        Binding::Key(key)
+    }
+}
+
+impl From<MouseBtn> for Binding {
+    fn from(button: MouseBtn) -> Self {
+        Binding::Mouse(button)
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl From<GamepadButton> for Binding {
+    fn from(button: GamepadButton) -> Self {
+        Binding::GamepadButton(button)
+    }
+}
+
+/// An input that gives a value between `-1.0` and `1.0`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AxisBinding {
+    /// `-1.0` while `negative` is down, `1.0` while `positive` is down and `0.0` when both or
+    /// none are
+    Buttons {
+        /// The input going towards `-1.0`
+        negative: Binding,
+        /// The input going towards `1.0`
+        positive: Binding,
+    },
+    /// An axis of the gamepads, with the deadzone applied
+    #[cfg(feature = "gamepad")]
+    Gamepad {
+        /// The axis
+        axis: GamepadAxis,
+        /// Flip the sign of the value
+        inverted: bool,
+    },
+}
+
+impl AxisBinding {
+    /// Create an [`AxisBinding::Buttons`] from two keys
+    #[must_use]
+    pub const fn keys(negative: Keycodes, positive: Keycodes) -> Self {
+        AxisBinding::Buttons {
+            negative: Binding::Key(negative),
+            positive: Binding::Key(positive),
+        }
+    }
+
+    /// Create an [`AxisBinding::Gamepad`] that isn't inverted
+    #[cfg(feature = "gamepad")]
+    #[must_use]
+    pub const fn gamepad(axis: GamepadAxis) -> Self {
+        AxisBinding::Gamepad {
+            axis,
+            inverted: false,
+        }
+    }
+
+    /// Get the value of the input, gamepad axes are read on every connected gamepad and the
+    /// one pushed the furthest wins
+    #[must_use]
+    pub fn value(self, engine: &Engine) -> f32 {
+        self.read(engine, PadFilter::default())
+    }
+
+    fn read(self, engine: &Engine, gamepad: PadFilter) -> f32 {
+        match self {
+            AxisBinding::Buttons { negative, positive } => {
+                let down = |binding: Binding| {
+                    let input = binding.read(engine, gamepad);
+                    if input.pressed || input.held {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                };
+                down(positive) - down(negative)
+            }
+            #[cfg(feature = "gamepad")]
+            AxisBinding::Gamepad { axis, inverted } => {
+                let value = match gamepad {
+                    Some(id) => engine.get_gamepad_axis(id, axis),
+                    None => engine
+                        .gamepads()
+                        .map(|id| engine.get_gamepad_axis(id, axis))
+                        .fold(0.0, |a: f32, b| if b.abs() > a.abs() { b } else { a }),
+                };
+                if inverted {
+                    -value
+                } else {
+                    value
+                }
+            }
+        }
+    }
+}
+
+/// Merge the status of several inputs triggering the same thing
+///
+/// It is pressed when one of the inputs is pressed while none was down, and released when the
+/// last input that was down is released
+fn combine(inputs: impl Iterator<Item = Input>) -> Input {
+    let (mut pressed, mut held, mut released, mut still_down) = (false, false, false, false);
+    for input in inputs {
+        pressed |= input.pressed;
+        held |= input.held;
+        released |= input.released;
+        still_down |= input.held || (input.pressed && !input.released);
+    }
+    Input::new(pressed && !held, held, released && !still_down)
+}
+
+/// Named actions and axes, each bound to any number of inputs
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionMap {
+    actions: BTreeMap<String, Vec<Binding>>,
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+    /// The gamepad read by the gamepad bindings
+    #[cfg(feature = "gamepad")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    gamepad: Option<GamepadId>,
+}
+
+impl ActionMap {
+    /// Create a map without any action nor axis
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an input to an action, the action is created if needed
+    ///
+    /// Nothing is done if the input is already bound to the action
+    pub fn bind(&mut self, action: &str, binding: impl Into<Binding>) -> &mut Self {
+        let binding = binding.into();
+        let bindings = self.actions.entry(action.to_owned()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Remove an input from an action
+    pub fn unbind(&mut self, action: &str, binding: impl Into<Binding>) -> &mut Self {
+        let binding = binding.into();
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|b| *b != binding);
+        }
+        self
+    }
+
+    /// Replace every input of an action, this is what a rebinding menu uses
+    pub fn set_bindings(&mut self, action: &str, bindings: Vec<Binding>) -> &mut Self {
+        self.actions.insert(action.to_owned(), bindings);
+        self
+    }
+
+    /// Return the inputs of an action, in the order they were bound
+    #[must_use]
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Return the name of every action, sorted
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(String::as_str)
+    }
+
+    /// Add an input to an axis, the axis is created if needed
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) -> &mut Self {
+        let bindings = self.axes.entry(axis.to_owned()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Remove an input from an axis
+    pub fn unbind_axis(&mut self, axis: &str, binding: AxisBinding) -> &mut Self {
+        if let Some(bindings) = self.axes.get_mut(axis) {
+            bindings.retain(|b| *b != binding);
+        }
+        self
+    }
+
+    /// Replace every input of an axis
+    pub fn set_axis_bindings(&mut self, axis: &str, bindings: Vec<AxisBinding>) -> &mut Self {
+        self.axes.insert(axis.to_owned(), bindings);
+        self
+    }
+
+    /// Return the inputs of an axis, in the order they were bound
+    #[must_use]
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map_or(&[], Vec::as_slice)
+    }
+
+    /// Return the name of every axis, sorted
+    pub fn axes(&self) -> impl Iterator<Item = &str> {
+        self.axes.keys().map(String::as_str)
+    }
+
+    /// Only read the given gamepad, `None` (the default) reads every connected gamepad
+    ///
+    /// Local multiplayer games can use a map per player, each with its own gamepad. This isn't
+    /// serialized since the ids change between runs
+    #[cfg(feature = "gamepad")]
+    pub fn set_gamepad(&mut self, gamepad: Option<GamepadId>) -> &mut Self {
+        self.gamepad = gamepad;
+        self
+    }
+
+    /// Return the gamepad read by the gamepad bindings
+    #[cfg(feature = "gamepad")]
+    #[must_use]
+    pub fn gamepad(&self) -> Option<GamepadId> {
+        self.gamepad
+    }
+
+    #[cfg_attr(not(feature = "gamepad"), allow(clippy::unused_self))]
+    fn gamepad_filter(&self) -> PadFilter {
+        #[cfg(feature = "gamepad")]
+        return self.gamepad;
+        #[cfg(not(feature = "gamepad"))]
+        None
+    }
+
+    /// Get the status of an action, an unknown action is never pressed
+    #[must_use]
+    pub fn action(&self, engine: &Engine, action: &str) -> Input {
+        combine(
+            self.bindings(action)
+                .iter()
+                .map(|binding| binding.read(engine, self.gamepad_filter())),
+        )
+    }
+
+    /// Get the value of an axis between `-1.0` and `1.0`, the input pushed the furthest wins
+    ///
+    /// An unknown axis is always `0.0`
+    #[must_use]
+    pub fn axis(&self, engine: &Engine, axis: &str) -> f32 {
+        self.axis_bindings(axis)
+            .iter()
+            .map(|binding| binding.read(engine, self.gamepad_filter()))
+            .fold(0.0, |a: f32, b| if b.abs() > a.abs() { b } else { a })
+            .clamp(-1.0, 1.0)
+    }
+}
+
+impl Engine {
+    /// Get the status of an action of the engine's [`ActionMap`]
+    #[must_use]
+    pub fn action(&self, action: &str) -> Input {
+        self.actions.action(self, action)
+    }
+
+    /// Get the value of an axis of the engine's [`ActionMap`]
+    #[must_use]
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.actions.axis(self, axis)
+    }
+
+    /// Return the [`ActionMap`] used by [`Engine::action`] and [`Engine::axis`]
+    #[must_use]
+    pub fn actions(&self) -> &ActionMap {
+        &self.actions
+    }
+
+    /// Return the [`ActionMap`] used by [`Engine::action`] and [`Engine::axis`], to change the
+    /// bindings
+    pub fn actions_mut(&mut self) -> &mut ActionMap {
+        &mut self.actions
+    }
+
+    /// Return an input that was pressed during this frame, if there is one
+    ///
+    /// Calling it every frame until it returns something is a "press any key" prompt. The
+    /// input that opened the prompt is pressed on that frame, so start asking on the next one.
+    #[must_use]
+    pub fn pressed_binding(&self) -> Option<Binding> {
+        if let Some(key) = self.k_pressed.iter().next() {
+            return Some(Binding::Key(*key));
+        }
+        if let Some((button, _)) = self.mouse.buttons.iter().find(|(_, input)| input.pressed) {
+            return Some(Binding::Mouse(*button));
+        }
+        #[cfg(feature = "gamepad")]
+        for event in self.get_gamepad_events() {
+            if let super::gamepad::GamepadEvent::ButtonPressed(id, button) = *event {
+                if self.actions.gamepad.is_none_or(|gamepad| gamepad == id) {
+                    return Some(Binding::GamepadButton(button));
+                }
+            }
+        }
+        None
+    }
+}
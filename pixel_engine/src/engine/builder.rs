@@ -0,0 +1,124 @@
+use super::{EngineWrapper, PresentMode, ScaleMode, Sprite};
+use px_draw::graphics::Color;
+use px_draw::vector2::Vu2d;
+
+/// Configuration of the window and the [`Engine`](crate::Engine)
+///
+/// ```no_run
+/// # fn update(_: &mut pixel_engine::Engine) -> Result<bool, Box<dyn std::error::Error>> { Ok(true) }
+/// let engine = pixel_engine::EngineBuilder::new("My Game", (320, 180), 4)
+///     .resizable(true)
+///     .present_mode(pixel_engine::PresentMode::Vsync)
+///     .show_fps(false)
+///     .build_sync();
+/// engine.run(update);
+/// ```
+#[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct EngineBuilder {
+    pub(crate) title: String,
+    pub(crate) size: (u32, u32, u32),
+    pub(crate) resizable: bool,
+    pub(crate) fullscreen: bool,
+    pub(crate) icon: Option<Sprite>,
+    pub(crate) clear_color: Color,
+    pub(crate) present_mode: PresentMode,
+    pub(crate) show_fps: bool,
+    pub(crate) canvas: Option<String>,
+    pub(crate) scale_mode: ScaleMode,
+}
+
+impl EngineBuilder {
+    /// Create a builder for a screen of `size` pixels, each one being `scale` pixels wide on the
+    /// window
+    pub fn new(title: impl AsRef<str>, size: impl Into<Vu2d>, scale: u32) -> Self {
+        let size = size.into();
+        Self {
+            title: title.as_ref().to_string(),
+            size: (size.x, size.y, scale),
+            resizable: false,
+            fullscreen: false,
+            icon: None,
+            clear_color: Color::BLACK,
+            present_mode: PresentMode::default(),
+            show_fps: true,
+            canvas: None,
+            scale_mode: ScaleMode::default(),
+        }
+    }
+
+    /// Allow the user to resize the window, `false` by default
+    #[must_use]
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Start in borderless fullscreen on the current monitor, `false` by default
+    #[must_use]
+    pub fn fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Set how the screen is scaled when the window doesn't have the size of the screen,
+    /// [`ScaleMode::Integer`] by default
+    #[must_use]
+    pub fn scale_mode(mut self, mode: ScaleMode) -> Self {
+        self.scale_mode = mode;
+        self
+    }
+
+    /// Set the icon of the window
+    #[must_use]
+    pub fn icon(mut self, icon: Sprite) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Set the color drawn behind the screen, visible through its transparent pixels, black by
+    /// default
+    #[must_use]
+    pub fn clear_color(mut self, color: Color) -> Self {
+        self.clear_color = color;
+        self
+    }
+
+    /// Set how the frames are shown on the screen
+    ///
+    /// See [`Engine::set_present_mode`](crate::Engine::set_present_mode)
+    #[must_use]
+    pub fn present_mode(mut self, mode: PresentMode) -> Self {
+        self.present_mode = mode;
+        self
+    }
+
+    /// Show the frame rate in the window's title as `Title - fps`, `true` by default
+    #[must_use]
+    pub fn show_fps(mut self, show_fps: bool) -> Self {
+        self.show_fps = show_fps;
+        self
+    }
+
+    /// Set the id of the HTML element the canvas is added to when targeting wasm32
+    ///
+    /// Defaults to the `PIXEL_ENGINE_CANVAS` environment variable at compile time, or to the
+    /// document's body if it isn't set
+    #[must_use]
+    pub fn canvas(mut self, id: impl AsRef<str>) -> Self {
+        self.canvas = Some(id.as_ref().to_string());
+        self
+    }
+
+    /// Create the window and the engine
+    pub async fn build(self) -> EngineWrapper {
+        EngineWrapper::from_builder(self).await
+    }
+
+    /// Create the window and the engine
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn build_sync(self) -> EngineWrapper {
+        futures::executor::block_on(self.build())
+    }
+}
@@ -2,6 +2,7 @@
 
 use px_backend::decals;
 use px_draw::graphics::Color;
+use px_draw::rect::Rectf;
 use px_draw::vector2::Vf2d;
 
 thread_local! {pub(crate) static DECAL_HANDLER: std::cell::RefCell<std::collections::VecDeque<decals::Decal>> = std::cell::RefCell::new(std::collections::VecDeque::with_capacity(16));}
@@ -16,7 +17,10 @@ pub struct Decal(
 
 impl Drop for Decal {
     fn drop(&mut self) {
-        DECAL_HANDLER.with(|key| key.borrow_mut().push_back(self.0.clone()));
+        // A detached decal has no texture to destroy
+        if !self.0.is_detached() {
+            DECAL_HANDLER.with(|key| key.borrow_mut().push_back(self.0.clone()));
+        }
     }
 }
 
@@ -29,6 +33,14 @@ impl Decal {
         )
     }
 
+    /// A decal for an engine without a GPU, it is never drawn
+    pub(crate) fn detached(spr: &px_draw::graphics::Sprite) -> Self {
+        Decal(
+            decals::Decal::detached((spr.width(), spr.height())),
+            std::marker::PhantomData,
+        )
+    }
+
     /// Get the size of the decal in pixel
     #[must_use]
     pub fn size(&self) -> px_draw::vector2::Vu2d {
@@ -113,6 +125,31 @@ pub trait DecalDraw {
     ) {
         self.draw_partial_decal_tinted(pos, decal, source_pos, source_size, Color::WHITE);
     }
+    /// Same as [`DecalDraw::draw_partial_decal`] with the source given as a
+    /// [`Rect`](px_draw::rect::Rect)
+    #[inline]
+    fn draw_partial_decal_area<P: Into<Vf2d> + Copy>(
+        &mut self,
+        pos: P,
+        decal: &Decal,
+        source: impl Into<Rectf>,
+    ) {
+        let source = source.into();
+        self.draw_partial_decal_tinted(pos.into(), decal, source.pos, source.size, Color::WHITE);
+    }
+    /// Same as [`DecalDraw::draw_partial_decal_tinted`] with the source given as a
+    /// [`Rect`](px_draw::rect::Rect)
+    #[inline]
+    fn draw_partial_decal_area_tinted<P: Into<Vf2d> + Copy>(
+        &mut self,
+        pos: P,
+        decal: &Decal,
+        source: impl Into<Rectf>,
+        tint: Color,
+    ) {
+        let source = source.into();
+        self.draw_partial_decal_tinted(pos.into(), decal, source.pos, source.size, tint);
+    }
     /// Draw a partial decal with a given scale
     #[inline]
     fn draw_partial_decal_scaled<P: Into<Vf2d> + Copy>(
@@ -352,7 +389,7 @@ impl DecalDraw for crate::Engine {
             );
             di.uv[i] = (uv[i].x, uv[i].y);
         }
-        self.handler.draw_decal_instance(di);
+        self.draw_decal_instance(di);
     }
     fn draw_decal<P: Into<Vf2d> + Copy>(&mut self, pos: P, decal: &Decal) {
         self.draw_decal_tinted(pos, decal, Color::WHITE);
@@ -475,19 +512,18 @@ impl DecalDraw for crate::Engine {
             },
             screen_size
         );
-        self.handler
-            .draw_decal_instance(px_backend::decals::DecalInstances {
-                id: decal.0.id(),
-                pos: [
-                    (topleft.x, topleft.y),         // A
-                    (topleft.x, bottomright.y),     // B
-                    (bottomright.x, bottomright.y), // C
-                    (bottomright.x, topleft.y),     // D
-                ],
-                uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
-                w: [1.0; 4],
-                tint: tint.into(),
-            });
+        self.draw_decal_instance(px_backend::decals::DecalInstances {
+            id: decal.0.id(),
+            pos: [
+                (topleft.x, topleft.y),         // A
+                (topleft.x, bottomright.y),     // B
+                (bottomright.x, bottomright.y), // C
+                (bottomright.x, topleft.y),     // D
+            ],
+            uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
+            w: [1.0; 4],
+            tint: tint.into(),
+        });
     }
 
     fn draw_decal_scaled_tinted<P: Into<Vf2d> + Copy>(
@@ -509,20 +545,19 @@ impl DecalDraw for crate::Engine {
             },
             screen_size
         );
-        self.handler
-            .draw_decal_instance(px_backend::decals::DecalInstances {
-                id: decal.0.id(),
-                pos: [
-                    (topleft.x, topleft.y),         // A
-                    (topleft.x, bottomright.y),     // B
-                    (bottomright.x, bottomright.y), // C
-                    (bottomright.x, topleft.y),     // D
-                ],
-                uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
-                w: [1.0; 4],
-
-                tint: tint.into(),
-            });
+        self.draw_decal_instance(px_backend::decals::DecalInstances {
+            id: decal.0.id(),
+            pos: [
+                (topleft.x, topleft.y),         // A
+                (topleft.x, bottomright.y),     // B
+                (bottomright.x, bottomright.y), // C
+                (bottomright.x, topleft.y),     // D
+            ],
+            uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
+            w: [1.0; 4],
+
+            tint: tint.into(),
+        });
     }
 
     #[inline]
@@ -555,19 +590,18 @@ impl DecalDraw for crate::Engine {
         uv[2] = (uv_bottomright.x, uv_bottomright.y);
         uv[3] = (uv_bottomright.x, uv_topleft.y);
 
-        self.handler
-            .draw_decal_instance(px_backend::decals::DecalInstances {
-                id: decal.0.id(),
-                pos: [
-                    (topleft.x, topleft.y),         // A
-                    (topleft.x, bottomright.y),     // B
-                    (bottomright.x, bottomright.y), // C
-                    (bottomright.x, topleft.y),     // D
-                ],
-                uv,
-                w: [1.0; 4],
-                tint: tint.into(),
-            });
+        self.draw_decal_instance(px_backend::decals::DecalInstances {
+            id: decal.0.id(),
+            pos: [
+                (topleft.x, topleft.y),         // A
+                (topleft.x, bottomright.y),     // B
+                (bottomright.x, bottomright.y), // C
+                (bottomright.x, topleft.y),     // D
+            ],
+            uv,
+            w: [1.0; 4],
+            tint: tint.into(),
+        });
     }
 
     #[inline]
@@ -601,19 +635,18 @@ impl DecalDraw for crate::Engine {
         uv[2] = (uv_bottomright.x, uv_bottomright.y);
         uv[3] = (uv_bottomright.x, uv_topleft.y);
 
-        self.handler
-            .draw_decal_instance(px_backend::decals::DecalInstances {
-                id: decal.0.id(),
-                pos: [
-                    (topleft.x, topleft.y),         // A
-                    (topleft.x, bottomright.y),     // B
-                    (bottomright.x, bottomright.y), // C
-                    (bottomright.x, topleft.y),     // D
-                ],
-                uv,
-                w: [1.0; 4],
-                tint: tint.into(),
-            });
+        self.draw_decal_instance(px_backend::decals::DecalInstances {
+            id: decal.0.id(),
+            pos: [
+                (topleft.x, topleft.y),         // A
+                (topleft.x, bottomright.y),     // B
+                (bottomright.x, bottomright.y), // C
+                (bottomright.x, topleft.y),     // D
+            ],
+            uv,
+            w: [1.0; 4],
+            tint: tint.into(),
+        });
     }
 
     #[inline]
@@ -673,7 +706,7 @@ impl DecalDraw for crate::Engine {
                 di.w[i] *= q;
                 di.pos[i] = (pos[i].x, pos[i].y);
             }
-            self.handler.draw_decal_instance(di);
+            self.draw_decal_instance(di);
         }
     }
 
@@ -745,7 +778,7 @@ impl DecalDraw for crate::Engine {
                 di.w[i] *= q;
                 di.pos[i] = (pos[i].x, pos[i].y);
             }
-            self.handler.draw_decal_instance(di);
+            self.draw_decal_instance(di);
         }
     }
 
@@ -790,7 +823,7 @@ impl DecalDraw for crate::Engine {
             di.w[i] = 1;
             */
         }
-        self.handler.draw_decal_instance(decals::DecalInstances {
+        self.draw_decal_instance(decals::DecalInstances {
             id: decal.0.id(),
             pos: [
                 (pos_arr[0].x, pos_arr[0].y),
@@ -836,7 +869,7 @@ impl DecalDraw for crate::Engine {
                 screen_size
             );
         }
-        self.handler.draw_decal_instance(decals::DecalInstances {
+        self.draw_decal_instance(decals::DecalInstances {
             id: decal.0.id(),
             pos: [
                 (pos_arr[0].x, pos_arr[0].y),
@@ -890,7 +923,7 @@ impl DecalDraw for crate::Engine {
             );
         }
 
-        self.handler.draw_decal_instance(decals::DecalInstances {
+        self.draw_decal_instance(decals::DecalInstances {
             id: decal.0.id(),
             pos: [
                 (pos_arr[0].x, pos_arr[0].y),
@@ -946,7 +979,7 @@ impl DecalDraw for crate::Engine {
                 screen_size
             );
         }
-        self.handler.draw_decal_instance(decals::DecalInstances {
+        self.draw_decal_instance(decals::DecalInstances {
             id: decal.0.id(),
             pos: [
                 (pos_arr[0].x, pos_arr[0].y),
@@ -29,8 +29,48 @@ pub trait Game: Sized {
     /// `Ok(false)` will gracefully shut down the program
     /// `Err(_)` will print out the error onto stderr and stop the program
     fn update(&mut self, engine: &mut crate::Engine) -> Result<bool, Box<dyn std::error::Error>>;
+    #[allow(clippy::missing_errors_doc)]
+    /// This is called at a constant rate when the engine has a
+    /// [`FixedTimestep`](crate::timestep::FixedTimestep), before [`Game::update`]. It may be
+    /// called several times in a frame to catch up, or not at all if the frame was short.
+    ///
+    /// [`Engine::elapsed`](crate::Engine::elapsed) is the duration of a step, and the inputs are
+    /// the ones of the frame the step runs in.
+    ///
+    /// # Returns
+    /// Same as [`Game::update`], `Ok(false)` or `Err(_)` will stop the program
+    fn fixed_update(
+        &mut self,
+        _engine: &mut crate::Engine,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(true)
+    }
     /// This function will be called when the input mode finishes.
     fn receive_input(&mut self, _engine: &mut crate::Engine, _input: String) {}
+    /// This function will be called when the window is resized, with its new size in physical
+    /// pixels. The screen keeps its size, see [`Engine::viewport`](crate::Engine::viewport)
+    fn on_resize(&mut self, _engine: &mut crate::Engine, _window_size: px_draw::vector2::Vu2d) {}
+    /// This function will be called when the window is asked to close, by its close button or by
+    /// the system. Returning `false` keeps the game running, to ask the player to save their work
+    /// first for example. Returning `true` runs [`Game::update`] one last time before the game
+    /// stops, unless the application is [suspended](Game::on_suspend).
+    ///
+    /// It isn't called when the game stops itself from [`Game::update`]
+    fn on_exit(&mut self, _engine: &mut crate::Engine) -> bool {
+        true
+    }
+    /// This function will be called when the window gains or loses the keyboard focus, see
+    /// [`Engine::is_focused`](crate::Engine::is_focused)
+    fn on_focus_changed(&mut self, _engine: &mut crate::Engine, _focused: bool) {}
+    /// This function will be called when the system suspends the application, like when a mobile
+    /// app goes to the background. No frame is run until [`Game::on_resume`] is called
+    fn on_suspend(&mut self, _engine: &mut crate::Engine) {}
+    /// This function will be called when the application resumes after [`Game::on_suspend`], the
+    /// time spent suspended isn't part of the [`elapsed`](crate::Engine::elapsed) time of the next
+    /// frame
+    fn on_resume(&mut self, _engine: &mut crate::Engine) {}
+    /// This function will be called when a file is dragged and dropped onto the window
+    fn on_file_dropped(&mut self, _engine: &mut crate::Engine, _path: std::path::PathBuf) {}
 }
 
 impl<F: (FnMut(&mut crate::Engine) -> Result<bool, Box<dyn std::error::Error>>) + 'static> Game
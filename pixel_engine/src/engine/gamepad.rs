@@ -0,0 +1,564 @@
+//! Gamepad input
+//!
+//! The engine reads the gamepads from a [`GamepadSource`]. With the `gilrs` feature the
+//! connected pads are read with [gilrs](https://docs.rs/gilrs), otherwise (and in
+//! [`Headless`](crate::Headless)) there is no source until one is given with
+//! [`Engine::set_gamepad_source`]. [`VirtualGamepads`] is a source controlled by the program,
+//! useful for tests.
+//!
+//! Buttons use the same [`Input`] model as the keyboard: pressed on the frame the button goes
+//! down, held while it stays down and released on the frame it goes up.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+
+use super::inputs::Input;
+use crate::vector2::Vf2d;
+use crate::Engine;
+
+/// Identify a gamepad, ids of disconnected pads may be reused by the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadId(pub usize);
+
+/// A gamepad button, named after their position on the pad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadButton {
+    /// The bottom face button (A on Xbox pads, Cross on Sony pads)
+    South,
+    /// The right face button (B on Xbox pads, Circle on Sony pads)
+    East,
+    /// The top face button (Y on Xbox pads, Triangle on Sony pads)
+    North,
+    /// The left face button (X on Xbox pads, Square on Sony pads)
+    West,
+    /// The left shoulder button
+    LeftBumper,
+    /// The right shoulder button
+    RightBumper,
+    /// The left trigger, fully pressed. See [`GamepadAxis::LeftTrigger`] for its analog value
+    LeftTrigger,
+    /// The right trigger, fully pressed. See [`GamepadAxis::RightTrigger`] for its analog value
+    RightTrigger,
+    /// The left middle button (Select, Back, Share)
+    Select,
+    /// The right middle button (Start, Menu, Options)
+    Start,
+    /// The vendor button in the middle of the pad
+    Mode,
+    /// Pressing the left stick
+    LeftStick,
+    /// Pressing the right stick
+    RightStick,
+    /// Up on the directional pad
+    DPadUp,
+    /// Down on the directional pad
+    DPadDown,
+    /// Left on the directional pad
+    DPadLeft,
+    /// Right on the directional pad
+    DPadRight,
+}
+
+/// An analog input of a gamepad
+///
+/// Sticks go from `-1.0` to `1.0`, with the y axis going down like the screen. Triggers go from
+/// `0.0` (released) to `1.0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadAxis {
+    /// Horizontal position of the left stick
+    LeftStickX,
+    /// Vertical position of the left stick
+    LeftStickY,
+    /// Horizontal position of the right stick
+    RightStickX,
+    /// Vertical position of the right stick
+    RightStickY,
+    /// Analog value of the left trigger
+    LeftTrigger,
+    /// Analog value of the right trigger
+    RightTrigger,
+}
+
+/// One of the sticks of a gamepad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadStick {
+    /// The left stick
+    Left,
+    /// The right stick
+    Right,
+}
+
+impl GamepadStick {
+    /// Return the horizontal and vertical axes of the stick
+    #[must_use]
+    pub const fn axes(self) -> (GamepadAxis, GamepadAxis) {
+        match self {
+            GamepadStick::Left => (GamepadAxis::LeftStickX, GamepadAxis::LeftStickY),
+            GamepadStick::Right => (GamepadAxis::RightStickX, GamepadAxis::RightStickY),
+        }
+    }
+}
+
+/// Something that happened to a gamepad
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadEvent {
+    /// A gamepad was plugged in, with its name
+    Connected(GamepadId, String),
+    /// A gamepad was unplugged
+    Disconnected(GamepadId),
+    /// A button went down
+    ButtonPressed(GamepadId, GamepadButton),
+    /// A button went up
+    ButtonReleased(GamepadId, GamepadButton),
+    /// An axis moved to a new value, without any deadzone applied
+    AxisChanged(GamepadId, GamepadAxis, f32),
+}
+
+impl GamepadEvent {
+    /// Return the gamepad the event is about
+    #[must_use]
+    pub fn id(&self) -> GamepadId {
+        match *self {
+            GamepadEvent::Connected(id, _)
+            | GamepadEvent::Disconnected(id)
+            | GamepadEvent::ButtonPressed(id, _)
+            | GamepadEvent::ButtonReleased(id, _)
+            | GamepadEvent::AxisChanged(id, _, _) => id,
+        }
+    }
+}
+
+/// Where the engine reads the gamepads from
+pub trait GamepadSource {
+    /// Push every event that happened since the last call into `events`, in order
+    ///
+    /// This is called once at the start of every frame
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>);
+}
+
+#[derive(Debug, Default)]
+struct VirtualState {
+    next_id: usize,
+    queue: Vec<GamepadEvent>,
+}
+
+/// A [`GamepadSource`] driven by the program
+///
+/// Every clone shares the same pads, so a clone can be given to the engine and the other kept
+/// to simulate the pads. The events are seen by the engine on its next frame.
+///
+/// ```
+/// use pixel_engine::gamepad::{GamepadButton, GamepadId, VirtualGamepads};
+///
+/// struct Game {
+///     jumps: u32,
+/// }
+///
+/// impl pixel_engine::Game for Game {
+/// #     fn create(_: &mut pixel_engine::Engine) -> Result<Self, Box<dyn std::error::Error>> {
+/// #         Ok(Game { jumps: 0 })
+/// #     }
+///     fn update(&mut self, engine: &mut pixel_engine::Engine) -> Result<bool, Box<dyn std::error::Error>> {
+///         if engine.get_gamepad_button(GamepadId(0), GamepadButton::South).pressed {
+///             self.jumps += 1;
+///         }
+///         Ok(true)
+///     }
+/// }
+///
+/// let mut headless = pixel_engine::Headless::<Game>::new("test", (32, 32)).unwrap();
+/// let pads = VirtualGamepads::new();
+/// headless.engine_mut().set_gamepad_source(Some(Box::new(pads.clone())));
+///
+/// let pad = pads.connect("Virtual pad");
+/// pads.press(pad, GamepadButton::South);
+/// // The button is pressed on the first frame then held
+/// headless.step_n(3, 1.0 / 60.0).unwrap();
+/// assert_eq!(headless.game().jumps, 1);
+/// assert!(headless.engine().get_gamepad_button(pad, GamepadButton::South).held);
+///
+/// pads.release(pad, GamepadButton::South);
+/// pads.press(pad, GamepadButton::South);
+/// headless.step(1.0 / 60.0).unwrap();
+/// assert_eq!(headless.game().jumps, 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VirtualGamepads(Rc<RefCell<VirtualState>>);
+
+impl VirtualGamepads {
+    /// Create a source without any gamepad
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&self, event: GamepadEvent) {
+        self.0.borrow_mut().queue.push(event);
+    }
+
+    /// Plug a new gamepad in
+    #[must_use = "The id is needed to use the gamepad"]
+    pub fn connect(&self, name: &str) -> GamepadId {
+        let id = {
+            let mut state = self.0.borrow_mut();
+            state.next_id += 1;
+            GamepadId(state.next_id - 1)
+        };
+        self.push(GamepadEvent::Connected(id, name.to_owned()));
+        id
+    }
+
+    /// Unplug a gamepad
+    pub fn disconnect(&self, id: GamepadId) {
+        self.push(GamepadEvent::Disconnected(id));
+    }
+
+    /// Press a button
+    pub fn press(&self, id: GamepadId, button: GamepadButton) {
+        self.push(GamepadEvent::ButtonPressed(id, button));
+    }
+
+    /// Release a button
+    pub fn release(&self, id: GamepadId, button: GamepadButton) {
+        self.push(GamepadEvent::ButtonReleased(id, button));
+    }
+
+    /// Move an axis
+    pub fn set_axis(&self, id: GamepadId, axis: GamepadAxis, value: f32) {
+        self.push(GamepadEvent::AxisChanged(id, axis, value));
+    }
+}
+
+impl GamepadSource for VirtualGamepads {
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>) {
+        events.append(&mut self.0.borrow_mut().queue);
+    }
+}
+
+/// A [`GamepadSource`] reading the gamepads connected to the computer
+#[cfg(feature = "gilrs")]
+pub struct GilrsSource {
+    gilrs: gilrs::Gilrs,
+    /// Pads that were already connected when the source was created
+    connected: Vec<GamepadEvent>,
+}
+
+#[cfg(feature = "gilrs")]
+impl std::fmt::Debug for GilrsSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GilrsSource").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "gilrs")]
+impl GilrsSource {
+    /// Start reading the gamepads
+    ///
+    /// # Errors
+    ///
+    /// If the platform's gamepad api is not available
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let gilrs = gilrs::Gilrs::new()?;
+        let connected = gilrs
+            .gamepads()
+            .map(|(id, pad)| GamepadEvent::Connected(GamepadId(id.into()), pad.name().to_owned()))
+            .collect();
+        Ok(Self { gilrs, connected })
+    }
+
+    fn button(button: gilrs::Button) -> Option<GamepadButton> {
+        use gilrs::Button;
+        Some(match button {
+            Button::South => GamepadButton::South,
+            Button::East => GamepadButton::East,
+            Button::North => GamepadButton::North,
+            Button::West => GamepadButton::West,
+            Button::LeftTrigger => GamepadButton::LeftBumper,
+            Button::RightTrigger => GamepadButton::RightBumper,
+            Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+            Button::RightTrigger2 => GamepadButton::RightTrigger,
+            Button::Select => GamepadButton::Select,
+            Button::Start => GamepadButton::Start,
+            Button::Mode => GamepadButton::Mode,
+            Button::LeftThumb => GamepadButton::LeftStick,
+            Button::RightThumb => GamepadButton::RightStick,
+            Button::DPadUp => GamepadButton::DPadUp,
+            Button::DPadDown => GamepadButton::DPadDown,
+            Button::DPadLeft => GamepadButton::DPadLeft,
+            Button::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "gilrs")]
+impl GamepadSource for GilrsSource {
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>) {
+        use gilrs::{Axis, Button, EventType};
+        events.append(&mut self.connected);
+        while let Some(gilrs::Event {
+            id: gilrs_id,
+            event,
+            ..
+        }) = self.gilrs.next_event()
+        {
+            let id = GamepadId(gilrs_id.into());
+            events.push(match event {
+                EventType::Connected => {
+                    GamepadEvent::Connected(id, self.gilrs.gamepad(gilrs_id).name().to_owned())
+                }
+                EventType::Disconnected => GamepadEvent::Disconnected(id),
+                EventType::ButtonPressed(button, _) => match Self::button(button) {
+                    Some(button) => GamepadEvent::ButtonPressed(id, button),
+                    None => continue,
+                },
+                EventType::ButtonReleased(button, _) => match Self::button(button) {
+                    Some(button) => GamepadEvent::ButtonReleased(id, button),
+                    None => continue,
+                },
+                // The analog value of the triggers is reported as a button
+                EventType::ButtonChanged(Button::LeftTrigger2, value, _) => {
+                    GamepadEvent::AxisChanged(id, GamepadAxis::LeftTrigger, value)
+                }
+                EventType::ButtonChanged(Button::RightTrigger2, value, _) => {
+                    GamepadEvent::AxisChanged(id, GamepadAxis::RightTrigger, value)
+                }
+                // gilrs' y axes go up
+                EventType::AxisChanged(axis, value, _) => match axis {
+                    Axis::LeftStickX => {
+                        GamepadEvent::AxisChanged(id, GamepadAxis::LeftStickX, value)
+                    }
+                    Axis::LeftStickY => {
+                        GamepadEvent::AxisChanged(id, GamepadAxis::LeftStickY, -value)
+                    }
+                    Axis::RightStickX => {
+                        GamepadEvent::AxisChanged(id, GamepadAxis::RightStickX, value)
+                    }
+                    Axis::RightStickY => {
+                        GamepadEvent::AxisChanged(id, GamepadAxis::RightStickY, -value)
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            });
+        }
+    }
+}
+
+/// State of a connected gamepad
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Pad {
+    pub(crate) name: String,
+    /// A button that isn't in the map isn't pressed
+    pub(crate) buttons: HashMap<GamepadButton, Input>,
+    /// The buttons that are down, the state of a button pressed and released during the same
+    /// frame only says it was pressed and released
+    pub(crate) down: HashSet<GamepadButton>,
+    pub(crate) axes: HashMap<GamepadAxis, f32>,
+}
+
+pub(crate) struct Gamepads {
+    source: Option<Box<dyn GamepadSource>>,
+    pub(crate) pads: BTreeMap<GamepadId, Pad>,
+    /// Events of the current frame
+    events: Vec<GamepadEvent>,
+    deadzone: f32,
+}
+
+impl Gamepads {
+    pub(crate) fn new(source: Option<Box<dyn GamepadSource>>) -> Self {
+        Self {
+            source,
+            pads: BTreeMap::new(),
+            events: Vec::new(),
+            deadzone: 0.1,
+        }
+    }
+
+    /// The default source of a windowed engine
+    pub(crate) fn system() -> Self {
+        #[cfg(feature = "gilrs")]
+        {
+            Self::new(
+                GilrsSource::new()
+                    .ok()
+                    .map(|source| Box::new(source) as Box<dyn GamepadSource>),
+            )
+        }
+        #[cfg(not(feature = "gilrs"))]
+        Self::new(None)
+    }
+
+    /// Read the new events of the source
+    pub(crate) fn poll(&mut self) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+        if let Some(source) = self.source.as_mut() {
+            source.poll(&mut events);
+        }
+        events
+    }
+
+    /// Update the pads with an event
+    pub(crate) fn apply(&mut self, event: GamepadEvent) {
+        match &event {
+            GamepadEvent::Connected(id, name) => {
+                self.pads.insert(
+                    *id,
+                    Pad {
+                        name: name.clone(),
+                        ..Pad::default()
+                    },
+                );
+            }
+            GamepadEvent::Disconnected(id) => {
+                self.pads.remove(id);
+            }
+            GamepadEvent::ButtonPressed(id, button) => {
+                let pad = self.pads.entry(*id).or_default();
+                if pad.down.insert(*button) {
+                    pad.buttons
+                        .entry(*button)
+                        .or_insert(Input::default())
+                        .pressed = true;
+                }
+            }
+            GamepadEvent::ButtonReleased(id, button) => {
+                if let Some(pad) = self.pads.get_mut(id) {
+                    if pad.down.remove(button) {
+                        let input = pad.buttons.entry(*button).or_insert(Input::default());
+                        input.held = false;
+                        input.released = true;
+                    }
+                }
+            }
+            GamepadEvent::AxisChanged(id, axis, value) => {
+                self.pads
+                    .entry(*id)
+                    .or_default()
+                    .axes
+                    .insert(*axis, value.clamp(-1.0, 1.0));
+            }
+        }
+        self.events.push(event);
+    }
+
+    /// Move the buttons to their next state and forget the events of the frame
+    pub(crate) fn end_frame(&mut self) {
+        self.events.clear();
+        for pad in self.pads.values_mut() {
+            pad.buttons.retain(|button, input| {
+                *input = Input::new(false, pad.down.contains(button), false);
+                input.held
+            });
+        }
+    }
+}
+
+/// Scale `value` so it is 0 inside of the deadzone and goes smoothly to 1 outside
+fn rescale(value: f32, deadzone: f32) -> f32 {
+    if value <= deadzone {
+        0.0
+    } else {
+        ((value - deadzone) / (1.0 - deadzone)).min(1.0)
+    }
+}
+
+impl Engine {
+    /// Return the connected gamepads, sorted by id
+    pub fn gamepads(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.gamepads.pads.keys().copied()
+    }
+
+    /// Return true if the gamepad is connected
+    #[must_use]
+    pub fn is_gamepad_connected(&self, id: GamepadId) -> bool {
+        self.gamepads.pads.contains_key(&id)
+    }
+
+    /// Return the name of a connected gamepad
+    #[must_use]
+    pub fn gamepad_name(&self, id: GamepadId) -> Option<&str> {
+        self.gamepads.pads.get(&id).map(|pad| pad.name.as_str())
+    }
+
+    /// Get the status of a gamepad button, the status of a gamepad that isn't connected is
+    /// always [`Input::default`]
+    #[must_use]
+    pub fn get_gamepad_button(&self, id: GamepadId, button: GamepadButton) -> Input {
+        self.gamepads
+            .pads
+            .get(&id)
+            .and_then(|pad| pad.buttons.get(&button))
+            .copied()
+            .unwrap_or(Input::default())
+    }
+
+    /// Get the value of a gamepad axis, with the deadzone applied
+    ///
+    /// See [`Engine::get_gamepad_stick`] to read both axes of a stick
+    #[must_use]
+    pub fn get_gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        let value = self.get_gamepad_axis_raw(id, axis);
+        rescale(value.abs(), self.gamepads.deadzone).copysign(value)
+    }
+
+    /// Get the value of a gamepad axis as it was reported by the gamepad
+    #[must_use]
+    pub fn get_gamepad_axis_raw(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        self.gamepads
+            .pads
+            .get(&id)
+            .and_then(|pad| pad.axes.get(&axis))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Get the position of a stick, with the y axis going down
+    ///
+    /// The deadzone is applied to the distance from the center so diagonals aren't snapped to
+    /// the axes, and the result has a length of at most 1
+    #[must_use]
+    pub fn get_gamepad_stick(&self, id: GamepadId, stick: GamepadStick) -> Vf2d {
+        let (x, y) = stick.axes();
+        let pos = Vf2d {
+            x: self.get_gamepad_axis_raw(id, x),
+            y: self.get_gamepad_axis_raw(id, y),
+        };
+        let len = pos.mag();
+        if len == 0.0 {
+            return pos;
+        }
+        pos * (rescale(len, self.gamepads.deadzone) / len)
+    }
+
+    /// Return the gamepad events of the frame, in order
+    #[must_use]
+    pub fn get_gamepad_events(&self) -> &[GamepadEvent] {
+        &self.gamepads.events
+    }
+
+    /// Set the deadzone of the axes and sticks, values whose magnitude is below it are read as
+    /// 0. It is clamped between `0.0` and `0.99`, the default is `0.1`
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f32) {
+        self.gamepads.deadzone = deadzone.clamp(0.0, 0.99);
+    }
+
+    /// Return the deadzone of the axes and sticks
+    #[must_use]
+    pub fn gamepad_deadzone(&self) -> f32 {
+        self.gamepads.deadzone
+    }
+
+    /// Change where the gamepads are read from, `None` disables the gamepads
+    ///
+    /// The gamepads of the previous source are forgotten without any
+    /// [`GamepadEvent::Disconnected`]
+    pub fn set_gamepad_source(&mut self, source: Option<Box<dyn GamepadSource>>) {
+        self.gamepads.pads.clear();
+        self.gamepads.source = source;
+    }
+}
@@ -0,0 +1,332 @@
+//! Run a [`Game`] without a window
+//!
+//! The game is updated against an in-memory screen, which is what tests and batch rendering
+//! need: frames are stepped by hand with a fixed `elapsed`, inputs are scripted and the screen
+//! can be read back after any frame.
+
+use super::inputs::{Keycodes, MouseBtn, MouseWheel};
+use super::replay::{InputEvent, Recording};
+use super::{Engine, Game, Sprite};
+use px_draw::vector2::{Vf2d, Vu2d};
+
+/// A [`Game`] running on an [`Engine`] that has no window nor GPU
+///
+/// Everything drawn on the screen [`Sprite`] works like in a window, but
+/// [decals](super::decals::Decal) are never drawn since they live on the GPU.
+///
+/// Inputs given between two frames are seen by the next frame, the same way events received
+/// between two redraws are.
+///
+/// ```
+/// use pixel_engine::inputs::Keycodes;
+/// use pixel_engine::traits::*;
+/// use pixel_engine::{Color, Engine, Headless};
+///
+/// let mut x = 0;
+/// let update = move |engine: &mut Engine| -> Result<bool, Box<dyn std::error::Error>> {
+///     if engine.get_key(Keycodes::Right).any() {
+///         x += 1;
+///     }
+///     engine.clear(Color::BLACK);
+///     engine.draw((x, 0), Color::RED);
+///     Ok(true)
+/// };
+/// let mut game = Headless::with_game("Test", (16, 16), update);
+///
+/// game.press_key(Keycodes::Right);
+/// assert!(game.step_n(3, 1.0 / 60.0).unwrap());
+/// assert_eq!(game.screen().get_pixel(3, 0), Color::RED);
+/// ```
+#[derive(Debug)]
+pub struct Headless<G> {
+    engine: Engine,
+    game: G,
+    frames: u64,
+    running: bool,
+    suspended: bool,
+    closing: bool,
+}
+
+impl<G: Game> Headless<G> {
+    /// Create a headless [`Engine`] with a screen of `size` and create the game with
+    /// [`Game::create`]
+    ///
+    /// # Errors
+    ///
+    /// This will return the error returned by [`Game::create`]
+    pub fn new(
+        title: impl AsRef<str>,
+        size: impl Into<Vu2d>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let size = size.into();
+        let mut engine = Engine::new_headless(title.as_ref().to_string(), (size.x, size.y));
+        let game = G::create(&mut engine)?;
+        Ok(Self::from_parts(engine, game))
+    }
+
+    /// Create a headless [`Engine`] with a screen of `size` that runs an already existing game
+    pub fn with_game(title: impl AsRef<str>, size: impl Into<Vu2d>, game: G) -> Self {
+        let size = size.into();
+        Self::from_parts(
+            Engine::new_headless(title.as_ref().to_string(), (size.x, size.y)),
+            game,
+        )
+    }
+
+    fn from_parts(engine: Engine, game: G) -> Self {
+        Self {
+            engine,
+            game,
+            frames: 0,
+            running: true,
+            suspended: false,
+            closing: false,
+        }
+    }
+
+    /// Run a single frame of the game, `elapsed` seconds after the previous one
+    ///
+    /// Return `Ok(false)` if the game stopped, either now or during a previous frame. Once the
+    /// game stopped, it isn't updated anymore. No frame is run while the game is
+    /// [suspended](Headless::suspend).
+    ///
+    /// # Errors
+    ///
+    /// This will return the error returned by [`Game::update`], which also stops the game
+    pub fn step(&mut self, elapsed: f64) -> Result<bool, Box<dyn std::error::Error>> {
+        if !self.running || self.suspended {
+            return Ok(self.running);
+        }
+        let r = self.engine.frame(&mut self.game, elapsed);
+        self.engine.has_changed = false;
+        self.engine.end_frame();
+        self.frames += 1;
+        self.running = matches!(r, Ok(true)) && !self.closing;
+        r.map(|running| running && !self.closing)
+    }
+
+    /// Run `frames` frames of the game, each one `elapsed` seconds after the previous one
+    ///
+    /// This stops early if the game stops, see [`Headless::step`]
+    ///
+    /// # Errors
+    ///
+    /// This will return the error returned by [`Game::update`], which also stops the game
+    pub fn step_n(
+        &mut self,
+        frames: u32,
+        elapsed: f64,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        for _ in 0..frames {
+            if !self.step(elapsed)? {
+                return Ok(false);
+            }
+        }
+        Ok(self.running)
+    }
+
+    /// Replay a recording until its end, see [`Engine::start_replay`]. If the game is
+    /// [suspended](Headless::suspend), the replay goes on with the frames run once it resumes.
+    ///
+    /// Return `Ok(false)` if the game stopped during the replay
+    ///
+    /// # Errors
+    ///
+    /// This will return the error returned by [`Game::update`], which also stops the game
+    pub fn replay(&mut self, recording: Recording) -> Result<bool, Box<dyn std::error::Error>> {
+        self.engine.start_replay(recording);
+        while let Some((played, len)) = self.engine.replay_progress() {
+            if played == len {
+                self.engine.stop_replay();
+                break;
+            }
+            if self.suspended {
+                break;
+            }
+            // The elapsed time comes from the recording
+            if !self.step(0.0)? {
+                return Ok(false);
+            }
+        }
+        Ok(self.running)
+    }
+
+    /// Return false once the game stopped
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Return the number of frames that ran
+    #[inline]
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    fn input(&mut self, event: InputEvent) {
+        self.engine.input_event(&mut self.game, event);
+    }
+
+    /// Press a key, it will be held until [`Headless::release_key`] is called
+    pub fn press_key(&mut self, key: Keycodes) {
+        self.input(InputEvent::Key(key, true));
+    }
+
+    /// Release a key
+    pub fn release_key(&mut self, key: Keycodes) {
+        self.input(InputEvent::Key(key, false));
+    }
+
+    /// Type some text, only used when the engine is in input mode
+    ///
+    /// This only sends the characters, the keys themselves need to be pressed with
+    /// [`Headless::press_key`]
+    pub fn type_text(&mut self, text: impl AsRef<str>) {
+        for chr in text.as_ref().chars() {
+            self.input(InputEvent::Char(chr));
+        }
+    }
+
+    /// Move the mouse to the given pixel of the screen
+    pub fn move_mouse(&mut self, pos: impl Into<Vu2d>) {
+        self.input(InputEvent::MouseMove(pos.into().cast_f32()));
+    }
+
+    /// Move the mouse by `delta` without moving the cursor, like a mouse moving while the cursor
+    /// is [locked](super::inputs::CursorGrab::Locked)
+    pub fn move_mouse_by(&mut self, delta: impl Into<Vf2d>) {
+        let delta = delta.into();
+        self.input(InputEvent::MouseMotion(
+            f64::from(delta.x),
+            f64::from(delta.y),
+        ));
+    }
+
+    /// Move the cursor inside or outside of the window
+    pub fn set_mouse_inside(&mut self, inside: bool) {
+        self.input(InputEvent::MouseInside(inside));
+    }
+
+    /// Press a mouse button, it will be held until [`Headless::release_mouse`] is called
+    pub fn press_mouse(&mut self, btn: MouseBtn) {
+        self.input(InputEvent::MouseButton(btn, true));
+    }
+
+    /// Release a mouse button
+    pub fn release_mouse(&mut self, btn: MouseBtn) {
+        self.input(InputEvent::MouseButton(btn, false));
+    }
+
+    /// Scroll the mouse wheel during the next frame
+    pub fn scroll(&mut self, wheel: MouseWheel) {
+        self.input(InputEvent::Wheel(wheel));
+    }
+
+    /// Scroll by `delta` lines, like a mouse wheel
+    pub fn scroll_lines(&mut self, delta: impl Into<Vf2d>) {
+        self.input(InputEvent::ScrollLines(delta.into()));
+    }
+
+    /// Scroll by `delta` pixels, like a touchpad
+    pub fn scroll_pixels(&mut self, delta: impl Into<Vf2d>) {
+        self.input(InputEvent::ScrollPixels(delta.into()));
+    }
+
+    /// Resize the window to `size` physical pixels and notify the game with
+    /// [`Game::on_resize`]
+    ///
+    /// There is no window, but the mouse positions given to [`Headless::move_window_mouse`]
+    /// depend on its size
+    pub fn resize_window(&mut self, size: impl Into<Vu2d>) {
+        let size = size.into();
+        self.engine.resize_window((size.x, size.y));
+        self.game.on_resize(&mut self.engine, size);
+    }
+
+    /// Ask to close the window like its close button does. If [`Game::on_exit`] accepts it, the
+    /// game is updated one last time by the next [`Headless::step`] and then stops, or it stops
+    /// right away if it is [suspended](Headless::suspend)
+    ///
+    /// Return true if the game is stopping or stopped
+    pub fn request_close(&mut self) -> bool {
+        if self.running && !self.closing && self.game.on_exit(&mut self.engine) {
+            self.closing = true;
+            if self.suspended {
+                self.running = false;
+            }
+        }
+        self.closing || !self.running
+    }
+
+    /// Give or take the keyboard focus to the window and notify the game with
+    /// [`Game::on_focus_changed`]
+    pub fn set_focused(&mut self, focused: bool) {
+        self.engine.focused = focused;
+        self.game.on_focus_changed(&mut self.engine, focused);
+    }
+
+    /// Suspend the game and notify it with [`Game::on_suspend`], frames aren't run until
+    /// [`Headless::resume`] is called
+    pub fn suspend(&mut self) {
+        if !self.suspended {
+            self.suspended = true;
+            self.game.on_suspend(&mut self.engine);
+        }
+    }
+
+    /// Resume the game after [`Headless::suspend`] and notify it with [`Game::on_resume`]
+    pub fn resume(&mut self) {
+        if self.suspended {
+            self.suspended = false;
+            self.game.on_resume(&mut self.engine);
+        }
+    }
+
+    /// Drop a file onto the window and notify the game with [`Game::on_file_dropped`]
+    pub fn drop_file(&mut self, path: impl Into<std::path::PathBuf>) {
+        self.game.on_file_dropped(&mut self.engine, path.into());
+    }
+
+    /// Move the mouse to the given position in physical pixels of the window, see
+    /// [`Headless::resize_window`]
+    pub fn move_window_mouse(&mut self, pos: impl Into<Vf2d>) {
+        let pos = self.engine.window_to_screen(pos);
+        self.input(InputEvent::MouseMove(pos));
+    }
+
+    /// Return the screen as it was at the end of the last frame
+    #[inline]
+    pub fn screen(&self) -> &Sprite {
+        self.engine.screen.get_ref()
+    }
+
+    /// Return the engine
+    #[inline]
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    /// Return the engine
+    #[inline]
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    /// Return the game
+    #[inline]
+    pub fn game(&self) -> &G {
+        &self.game
+    }
+
+    /// Return the game
+    #[inline]
+    pub fn game_mut(&mut self) -> &mut G {
+        &mut self.game
+    }
+
+    /// Stop the engine and return the game
+    #[inline]
+    pub fn into_game(self) -> G {
+        self.game
+    }
+}
@@ -1,5 +1,5 @@
 use px_backend::winit;
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// A Simple Struct that Represent an Input
 pub struct Input {
     /// Is the input pressed on that frame
@@ -36,29 +36,41 @@ impl Input {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct Mouse {
-    /// State of each mouse button
-    /// 0 => None
-    /// 1 => Pressed
-    /// 2 => Held
-    /// 3 => Released
-    pub(crate) buttons: [Input; 3],
+    /// State of the mouse buttons, a button that isn't in the map isn't pressed
+    pub(crate) buttons: std::collections::HashMap<MouseBtn, Input>,
     pub(crate) pos: (u32, u32),
+    /// Position on the screen, not truncated nor clamped
+    pub(crate) precise_pos: (f32, f32),
+    /// Raw motion of the mouse during the frame
+    pub(crate) delta: (f64, f64),
+    /// Is the cursor inside of the window
+    pub(crate) inside: bool,
     pub(crate) wheel: MouseWheel,
+    /// Scrolling in lines during the frame
+    pub(crate) scroll_lines: (f32, f32),
+    /// Scrolling in pixels during the frame
+    pub(crate) scroll_pixels: (f64, f64),
 }
 
 impl Mouse {
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Mouse {
-            buttons: [Input::default(), Input::default(), Input::default()],
+            buttons: std::collections::HashMap::new(),
             pos: (0, 0),
+            precise_pos: (0.0, 0.0),
+            delta: (0.0, 0.0),
+            inside: false,
             wheel: MouseWheel::None,
+            scroll_lines: (0.0, 0.0),
+            scroll_pixels: (0.0, 0.0),
         }
     }
 }
 /// Represent a Mouse Button
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseBtn {
     /// The left click
     Left,
@@ -66,10 +78,56 @@ pub enum MouseBtn {
     Right,
     /// The left middle click (scroll wheel click)
     Middle,
+    /// The back button on the side of the mouse
+    Back,
+    /// The forward button on the side of the mouse
+    Forward,
+    /// Any other button, the number depends on the platform
+    Other(u16),
+}
+
+impl From<winit::event::MouseButton> for MouseBtn {
+    fn from(button: winit::event::MouseButton) -> Self {
+        use winit::event::MouseButton;
+        match button {
+            MouseButton::Left => MouseBtn::Left,
+            MouseButton::Right => MouseBtn::Right,
+            MouseButton::Middle => MouseBtn::Middle,
+            // The side buttons are reported as `Other` with a number depending on the platform
+            #[cfg(target_os = "windows")]
+            MouseButton::Other(1) => MouseBtn::Back,
+            #[cfg(target_os = "windows")]
+            MouseButton::Other(2) => MouseBtn::Forward,
+            // X11 buttons 8 and 9, Wayland's BTN_SIDE and BTN_EXTRA
+            #[cfg(not(any(target_os = "windows", target_os = "macos", target_arch = "wasm32")))]
+            MouseButton::Other(8 | 0x113) => MouseBtn::Back,
+            #[cfg(not(any(target_os = "windows", target_os = "macos", target_arch = "wasm32")))]
+            MouseButton::Other(9 | 0x114) => MouseBtn::Forward,
+            #[cfg(any(target_os = "macos", target_arch = "wasm32"))]
+            MouseButton::Other(3) => MouseBtn::Back,
+            #[cfg(any(target_os = "macos", target_arch = "wasm32"))]
+            MouseButton::Other(4) => MouseBtn::Forward,
+            MouseButton::Other(other) => MouseBtn::Other(other),
+        }
+    }
+}
+
+/// How the cursor is kept inside of the window, see
+/// [`Engine::set_cursor_grab`](crate::Engine::set_cursor_grab)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CursorGrab {
+    /// The cursor moves freely
+    #[default]
+    None,
+    /// The cursor can't leave the window
+    Confined,
+    /// The cursor stays where it is, use
+    /// [`Engine::get_mouse_delta`](crate::Engine::get_mouse_delta) to know how the mouse moves
+    Locked,
 }
 
 /// Represent a scroll wheel Direction
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MouseWheel {
     /// No Scroll
     None,
@@ -83,6 +141,31 @@ pub enum MouseWheel {
     Left,
 }
 
+impl MouseWheel {
+    /// The direction of the biggest component of a scroll delta
+    pub(crate) fn from_delta(delta: crate::vector2::Vf2d) -> Self {
+        let (x, y) = (delta.x, delta.y);
+        if x.abs() > y.abs() {
+            if x > 0.0 {
+                MouseWheel::Right
+            } else if x < 0.0 {
+                MouseWheel::Left
+            } else {
+                MouseWheel::None
+            }
+        } else if y > 0.0 {
+            MouseWheel::Down
+        } else if y < 0.0 {
+            MouseWheel::Up
+        } else {
+            MouseWheel::None
+        }
+    }
+}
+
+/// Number of pixels in a line of scrolling, used to add scrolling in pixels to scrolling in lines
+pub const PIXELS_PER_LINE: f64 = 20.0;
+
 pub use winit::event::VirtualKeyCode as Keycodes;
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
 /// Represent a Key
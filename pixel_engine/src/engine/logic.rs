@@ -1,15 +1,38 @@
+use super::actions::ActionMap;
+use super::builder::EngineBuilder;
 use super::decals::Decal;
-use super::inputs::{self, Input, Mouse, MouseBtn, MouseWheel};
+#[cfg(feature = "gamepad")]
+use super::gamepad::Gamepads;
+use super::inputs::{self, CursorGrab, Input, Mouse, MouseBtn, MouseWheel};
+use super::replay::{InputEvent, InputState, Playback};
+use super::text_input::{KeyAction, MemoryClipboard, TextInput};
+use super::timestep::{Accumulator, FixedTimestep};
 use super::Sprite;
 
 use pixel_engine_draw::traits::SmartDrawingTrait;
-use px_draw::graphics::DrawingSprite;
+use px_draw::graphics::{DrawingSprite, OverlappingError, SpriteMutRef};
 
-use pixel_engine_draw::vector2::Vu2d;
+use pixel_engine_draw::rect::Rectf;
+use pixel_engine_draw::vector2::{Vf2d, Vu2d};
 use px_backend::winit::{
     self,
-    event::{Event, WindowEvent},
+    event::{DeviceEvent, Event, WindowEvent},
 };
+use px_backend::PresentMode;
+
+/// How the screen is scaled to fill the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScaleMode {
+    /// Use the biggest integer scale that fits in the window, so every pixel has the same size.
+    /// When the window is smaller than the screen this behaves like [`ScaleMode::Fit`]
+    #[default]
+    Integer,
+    /// Use the biggest scale that fits in the window, pixels may have different sizes
+    Fit,
+    /// Fill the whole window, the screen is deformed if the window doesn't have the same aspect
+    /// ratio
+    Stretch,
+}
 
 /// A Wrapper around an Engine
 #[derive(Debug)]
@@ -32,17 +55,29 @@ impl std::ops::DerefMut for EngineWrapper {
 
 impl EngineWrapper {
     /// Create the Engine and the Wrapper
+    ///
+    /// See [`EngineBuilder`] for more options
     pub async fn new(title: String, size: (u32, u32, u32)) -> Self {
-        Self(Some(Engine::new(title, size).await))
+        EngineBuilder::new(title, (size.0, size.1), size.2)
+            .build()
+            .await
     }
     /// Create the Engine and the Wrapper
+    ///
+    /// See [`EngineBuilder`] for more options
     #[cfg(not(target_arch = "wasm32"))]
     #[must_use]
     pub fn new_sync(title: String, size: (u32, u32, u32)) -> Self {
-        Self(Some(Engine::new_sync(title, size)))
+        EngineBuilder::new(title, (size.0, size.1), size.2).build_sync()
+    }
+
+    pub(crate) async fn from_builder(builder: EngineBuilder) -> Self {
+        Self(Some(Engine::new(builder).await))
     }
 
     /// This will first create the game instance, then run the main loop of the game
+    ///
+    /// If the game can't be created the error is printed and the program exits with the code `1`
     pub fn run_init<G: crate::Game + 'static>(mut self) -> ! {
         let game = match G::create(&mut self) {
             Ok(game) => game,
@@ -52,7 +87,7 @@ impl EngineWrapper {
                 } else {
                     println!("Unable to create game instance:\n{}", e);
                 }
-                std::process::abort();
+                std::process::exit(1);
             }
         };
 
@@ -60,245 +95,309 @@ impl EngineWrapper {
     }
     /// The core of your program,
     /// This takes an already existing implementation of a [Game](crate::Game) `G`
-    #[allow(clippy::too_many_lines, clippy::missing_panics_doc)]
-    pub fn run<G: crate::Game + 'static>(mut self, mut game: G) -> ! {
+    ///
+    /// The program exits with the code `1` if the game stopped with an error
+    #[allow(clippy::missing_panics_doc)]
+    pub fn run<G: crate::Game + 'static>(mut self, game: G) -> ! {
         let mut engine = self.0.take().unwrap();
-        let mut force_exit = false;
-        let event_loop = engine.event_loop.take().unwrap();
-        let mut redraw = true;
-        let mut redraw_last_frame = false;
-        let mut ignore_next_char = false;
+        let event_loop = engine
+            .event_loop
+            .take()
+            .expect("A headless engine can't be run, use `Headless` instead");
+        let mut runner = Runner::new(engine, game);
         event_loop.run(move |e, _, control_flow| {
-            *control_flow = winit::event_loop::ControlFlow::Poll;
-            if redraw_last_frame {
-                for key in &engine.k_pressed {
-                    engine.k_held.insert(*key);
+            let destroyed = matches!(e, Event::LoopDestroyed);
+            runner.handle(e, control_flow);
+            if let (true, Some(Err(e))) = (destroyed, &runner.exit) {
+                if cfg!(debug_assertions) {
+                    println!("Game Stopped:\n{:?}", e);
+                } else {
+                    println!("Game Stopped:\n{}", e);
                 }
-                engine.k_pressed.clear();
-                engine.k_released.clear();
-                for i in 0..3 {
-                    if engine.mouse.buttons[i].released {
-                        engine.mouse.buttons[i].released = false;
-                    }
-                    if engine.mouse.buttons[i].pressed {
-                        engine.mouse.buttons[i].pressed = false;
-                        engine.mouse.buttons[i].held = true;
+            }
+        });
+    }
+
+    /// Same as [`EngineWrapper::run_init`], but returns once the game has stopped, see
+    /// [`EngineWrapper::run_return`]
+    ///
+    /// # Errors
+    /// Returns the error of [`Game::create`](crate::Game::create) if the game couldn't be created
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "android",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    #[allow(clippy::type_complexity)]
+    pub fn run_init_return<G: crate::Game>(
+        mut self,
+    ) -> Result<(Result<ExitReason, Box<dyn std::error::Error>>, G), Box<dyn std::error::Error>>
+    {
+        let game = G::create(&mut self)?;
+        Ok(self.run_return(game))
+    }
+    /// Same as [`EngineWrapper::run`], but returns once the game has stopped instead of exiting
+    /// the program. The window is closed and the game is given back with the reason it stopped,
+    /// or the error that stopped it.
+    ///
+    /// Another engine can be created afterwards, to run several games one after the other.
+    /// Only available on the platforms where winit can return from its event loop
+    ///
+    /// # Panics
+    /// Panics if the engine is headless
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "android",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub fn run_return<G: crate::Game>(
+        mut self,
+        game: G,
+    ) -> (Result<ExitReason, Box<dyn std::error::Error>>, G) {
+        use winit::platform::run_return::EventLoopExtRunReturn;
+
+        let mut engine = self.0.take().unwrap();
+        let mut event_loop = engine
+            .event_loop
+            .take()
+            .expect("A headless engine can't be run, use `Headless` instead");
+        let mut runner = Runner::new(engine, game);
+        event_loop.run_return(|e, _, control_flow| runner.handle(e, control_flow));
+        runner.engine.event_loop = Some(event_loop);
+        let Runner { game, exit, .. } = runner;
+        // The loop only stops once `exit` is set
+        (exit.unwrap_or(Ok(ExitReason::Closed)), game)
+    }
+}
+
+thread_local! {static EVENT_LOOP: std::cell::RefCell<Option<winit::event_loop::EventLoop<()>>> = const { std::cell::RefCell::new(None) };}
+
+/// Why a game run by [`EngineWrapper::run_return`] stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitReason {
+    /// [`Game::update`](crate::Game::update) or
+    /// [`Game::fixed_update`](crate::Game::fixed_update) returned `Ok(false)`
+    Stopped,
+    /// The window was closed and [`Game::on_exit`](crate::Game::on_exit) accepted it
+    Closed,
+}
+
+/// The state of the main loop, fed with the events of the window
+#[allow(clippy::struct_excessive_bools)]
+struct Runner<G: crate::Game> {
+    engine: Engine,
+    game: G,
+    main_window: winit::window::WindowId,
+    window_title: String,
+    redraw: bool,
+    redraw_last_frame: bool,
+    suspended: bool,
+    /// Set when the window was closed, the game is updated one last time before stopping
+    closing: bool,
+    /// Set when the loop has to stop
+    exit: Option<Result<ExitReason, Box<dyn std::error::Error>>>,
+}
+
+impl<G: crate::Game> Runner<G> {
+    fn new(engine: Engine, game: G) -> Self {
+        Self {
+            main_window: engine.window().id(),
+            window_title: engine.title.clone(),
+            engine,
+            game,
+            redraw: true,
+            redraw_last_frame: false,
+            suspended: false,
+            closing: false,
+            exit: None,
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn handle(&mut self, e: Event<'_, ()>, control_flow: &mut winit::event_loop::ControlFlow) {
+        let Self {
+            engine,
+            game,
+            main_window,
+            window_title,
+            redraw,
+            redraw_last_frame,
+            suspended,
+            closing,
+            exit,
+        } = self;
+        *control_flow = winit::event_loop::ControlFlow::Poll;
+        if *redraw_last_frame {
+            engine.end_frame();
+            *redraw_last_frame = false;
+        }
+        match e {
+            Event::WindowEvent {
+                event: e,
+                window_id,
+            } if window_id == *main_window => match e {
+                WindowEvent::KeyboardInput { input: inp, .. } => {
+                    if let Some(key) = inp.virtual_keycode {
+                        engine.input_event(
+                            game,
+                            InputEvent::Key(key, inp.state == winit::event::ElementState::Pressed),
+                        );
+                    } else {
+                        engine.ignore_next_char = false;
                     }
                 }
-                engine.mouse.wheel = MouseWheel::None;
-                redraw_last_frame = false;
-                crate::decals::DECAL_HANDLER.with(|key| {
-                    key.borrow_mut()
-                        .drain(..)
-                        .for_each(|decal| decal.destroy(&mut engine.handler));
-                });
-            }
-            match e {
-                Event::WindowEvent {
-                    event: e,
-                    window_id,
-                } if window_id == engine.window.id() => match e {
-                    WindowEvent::KeyboardInput { input: inp, .. } => {
-                        ignore_next_char = false;
-                        if !engine.input_toggle {
-                            if let Some(key) = inp.virtual_keycode {
-                                if inp.state == winit::event::ElementState::Released {
-                                    engine.k_pressed.remove(&key);
-                                    engine.k_held.remove(&key);
-                                    engine.k_released.insert(key);
-                                } else if !engine.k_held.contains(&key) {
-                                    engine.k_pressed.insert(key);
-                                }
-                            }
-                        } else if inp.state == px_backend::winit::event::ElementState::Pressed {
-                            match inp.virtual_keycode {
-                                Some(winit::event::VirtualKeyCode::Escape) => {
-                                    engine.input_toggle = false;
-                                }
-                                Some(winit::event::VirtualKeyCode::Back) => {
-                                    if !engine.input_buffer.is_empty() && engine.input_cursor > 0 {
-                                        engine.input_buffer.remove(engine.input_cursor - 1);
-                                        engine.input_cursor = engine.input_cursor.saturating_sub(1);
-                                    };
-                                }
-                                Some(winit::event::VirtualKeyCode::Return) => {
-                                    engine.finish_input = true;
-                                    let input = std::mem::replace(
-                                        &mut engine.input_buffer,
-                                        String::with_capacity(128),
-                                    );
-                                    engine.input_cursor = 0;
-                                    game.receive_input(&mut engine, input);
-                                }
-                                Some(winit::event::VirtualKeyCode::Left) => {
-                                    engine.input_cursor = engine.input_cursor.saturating_sub(1);
-                                }
-                                Some(winit::event::VirtualKeyCode::Right) => {
-                                    engine.input_cursor =
-                                        engine.input_buffer.len().min(engine.input_cursor + 1);
-                                }
-                                _ => {}
-                            }
-                        }
-                        if let Some(key) = inp.virtual_keycode {
-                            if engine.input_passthrough.contains(&key) {
-                                ignore_next_char = true;
-                                if inp.state == winit::event::ElementState::Released {
-                                    engine.k_pressed.remove(&key);
-                                    engine.k_held.remove(&key);
-                                    engine.k_released.insert(key);
-                                } else if !engine.k_held.contains(&key) {
-                                    engine.k_pressed.insert(key);
-                                }
-                            }
-                        }
+                WindowEvent::CloseRequested if !*closing => {
+                    *closing = game.on_exit(engine);
+                    // No frame runs while suspended, so there is no last update
+                    if *closing && *suspended {
+                        *exit = Some(Ok(ExitReason::Closed));
                     }
-                    WindowEvent::CloseRequested => {
-                        force_exit = true;
+                }
+                WindowEvent::Resized(size)
+                | WindowEvent::ScaleFactorChanged {
+                    new_inner_size: &mut size,
+                    ..
+                } => {
+                    engine.resize_window((size.width, size.height));
+                    game.on_resize(engine, (size.width, size.height).into());
+                }
+                WindowEvent::CursorMoved { position, .. } => {
+                    let (x, y): (f64, f64) = position.into();
+                    //events.push(Events::MouseMove(x, y));
+                    #[allow(clippy::cast_possible_truncation)]
+                    let pos = engine.window_to_screen(Vf2d {
+                        x: x as f32,
+                        y: y as f32,
+                    });
+                    engine.input_event(game, InputEvent::MouseMove(pos));
+                }
+                WindowEvent::CursorEntered { .. } => {
+                    engine.input_event(game, InputEvent::MouseInside(true));
+                }
+                WindowEvent::CursorLeft { .. } => {
+                    engine.input_event(game, InputEvent::MouseInside(false));
+                }
+                WindowEvent::Focused(focused) => {
+                    engine.focused = focused;
+                    game.on_focus_changed(engine, focused);
+                }
+                WindowEvent::DroppedFile(path) => {
+                    game.on_file_dropped(engine, path);
+                }
+                WindowEvent::MouseWheel { delta, .. } => match delta {
+                    winit::event::MouseScrollDelta::LineDelta(x, y) => {
+                        engine.input_event(game, InputEvent::ScrollLines(Vf2d { x, y }));
                     }
-                    WindowEvent::CursorMoved { position, .. } => {
-                        let (x, y): (f64, f64) = position.into();
-                        //events.push(Events::MouseMove(x, y));
-                        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-                        {
-                            engine.mouse.pos = (
-                                (x / f64::from(engine.scale())).trunc().abs() as u32,
-                                (y / f64::from(engine.scale())).trunc().abs() as u32,
-                            );
+                    winit::event::MouseScrollDelta::PixelDelta(lp) => {
+                        #[allow(clippy::cast_possible_truncation)]
+                        let delta = Vf2d {
+                            x: lp.x as f32,
+                            y: lp.y as f32,
                         };
+                        engine.input_event(game, InputEvent::ScrollPixels(delta));
                     }
-                    WindowEvent::MouseWheel { delta, .. } => match delta {
-                        winit::event::MouseScrollDelta::LineDelta(x, y) => {
-                            engine.mouse.wheel = if x.abs() > y.abs() {
-                                if x > 0.0 {
-                                    MouseWheel::Right
-                                } else if x < 0.0 {
-                                    MouseWheel::Left
-                                } else {
-                                    MouseWheel::None
-                                }
-                            } else if y > 0.0 {
-                                MouseWheel::Down
-                            } else if y < 0.0 {
-                                MouseWheel::Up
-                            } else {
-                                MouseWheel::None
-                            };
-                        }
-                        winit::event::MouseScrollDelta::PixelDelta(lp) => {
-                            let (x, y): (f64, f64) = lp.into();
-                            engine.mouse.wheel = if x.abs() > y.abs() {
-                                if x > 0.0 {
-                                    MouseWheel::Right
-                                } else if x < 0.0 {
-                                    MouseWheel::Left
-                                } else {
-                                    MouseWheel::None
-                                }
-                            } else if y > 0.0 {
-                                MouseWheel::Down
-                            } else if y < 0.0 {
-                                MouseWheel::Up
-                            } else {
-                                MouseWheel::None
-                            };
-                        }
-                    },
-                    WindowEvent::MouseInput { button, state, .. } => {
-                        if std::mem::discriminant(&button)
-                            != std::mem::discriminant(&winit::event::MouseButton::Other(0))
-                        {
-                            let btn = match button {
-                                winit::event::MouseButton::Left => MouseBtn::Left,
-                                winit::event::MouseButton::Right => MouseBtn::Right,
-                                winit::event::MouseButton::Middle => MouseBtn::Middle,
-                                winit::event::MouseButton::Other(_) => {
-                                    unreachable!("MouseButton::Other()")
-                                }
-                            };
-                            if state == winit::event::ElementState::Pressed {
-                                engine.mouse.buttons[match btn {
-                                    MouseBtn::Left => 0,
-                                    MouseBtn::Right => 1,
-                                    MouseBtn::Middle => 2,
-                                }]
-                                .pressed = true;
-                            } else {
-                                engine.mouse.buttons[match btn {
-                                    MouseBtn::Left => 0,
-                                    MouseBtn::Right => 1,
-                                    MouseBtn::Middle => 2,
-                                }]
-                                .released = true;
-                                engine.mouse.buttons[match btn {
-                                    MouseBtn::Left => 0,
-                                    MouseBtn::Right => 1,
-                                    MouseBtn::Middle => 2,
-                                }]
-                                .held = false;
-                            }
-                        }
-                    }
-                    WindowEvent::ReceivedCharacter(char)
-                        if engine.input_toggle
-                            && !(engine.ignore_passthrough_char && ignore_next_char)
-                            && char.is_ascii()
-                            && !char.is_control() =>
-                    {
-                        engine.input_buffer.insert(engine.input_cursor, char);
-                        engine.input_cursor += 1;
-                    }
-
-                    //event => println!("missed_event: {event:?}"),
-                    _ => {}
                 },
-                Event::RedrawRequested(_) => {
-                    redraw = true;
+                WindowEvent::MouseInput { button, state, .. } => {
+                    engine.input_event(
+                        game,
+                        InputEvent::MouseButton(
+                            button.into(),
+                            state == winit::event::ElementState::Pressed,
+                        ),
+                    );
                 }
-                Event::MainEventsCleared => {
-                    engine.window.request_redraw();
+                WindowEvent::ReceivedCharacter(char) => {
+                    engine.input_event(game, InputEvent::Char(char));
                 }
-                _ => {} //event => println!("missed_window_event: {event:?}"),
+
+                //event => println!("missed_event: {event:?}"),
+                _ => {}
+            },
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } if engine.focused => {
+                engine.input_event(game, InputEvent::MouseMotion(delta.0, delta.1));
+            }
+            Event::RedrawRequested(_) => {
+                *redraw = true;
             }
-            if redraw {
-                engine.elapsed = (instant::Instant::now()
-                    .checked_duration_since(engine.timer)
-                    .expect("Error with timer"))
-                .as_secs_f64();
-                // End
+            Event::Suspended if !*suspended => {
+                *suspended = true;
+                game.on_suspend(engine);
+            }
+            // Also sent when the loop starts on some platforms
+            Event::Resumed if *suspended => {
+                *suspended = false;
                 engine.timer = instant::Instant::now();
-                engine.frame_timer += engine.elapsed;
-                engine.frame_count += 1;
-                if engine.frame_timer > 1.0 {
-                    engine.frame_timer -= 1.0;
-                    engine
-                        .window
-                        .set_title(&format!("{} - {}fps", engine.title, engine.frame_count));
-                    engine.frame_count = 0;
-                }
-                let r = game.update(&mut engine);
-                if r.is_err() || r.as_ref().ok() == Some(&false) || force_exit {
-                    if let Err(e) = r {
-                        if cfg!(debug_assertions) {
-                            println!("Game Stopped:\n{:?}", e);
-                        } else {
-                            println!("Game Stopped:\n{}", e);
-                        }
-                    }
-                    *control_flow = winit::event_loop::ControlFlow::Exit;
+                game.on_resume(engine);
+            }
+            Event::MainEventsCleared => {
+                engine.window().request_redraw();
+            }
+            _ => {} //event => println!("missed_window_event: {event:?}"),
+        }
+        if *redraw && exit.is_none() && !*suspended {
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(fps) = engine.frame_limit {
+                super::timestep::wait_until(
+                    engine.timer + std::time::Duration::from_secs_f64(f64::from(fps).recip()),
+                );
+            }
+            let elapsed = (instant::Instant::now()
+                .checked_duration_since(engine.timer)
+                .expect("Error with timer"))
+            .as_secs_f64();
+            // End
+            engine.timer = instant::Instant::now();
+            engine.frame_timer += elapsed;
+            engine.frame_count += 1;
+            if engine.frame_timer > 1.0 {
+                engine.frame_timer -= 1.0;
+                if engine.show_fps {
+                    *window_title = format!("{} - {}fps", engine.title, engine.frame_count);
+                    engine.window().set_title(window_title);
                 }
+                engine.frame_count = 0;
+            }
+            if !engine.show_fps && engine.title != *window_title {
+                window_title.clone_from(&engine.title);
+                engine.window().set_title(window_title);
+            }
+            match engine.frame(game, elapsed) {
+                Ok(_) if *closing => *exit = Some(Ok(ExitReason::Closed)),
+                Ok(true) => {}
+                Ok(false) => *exit = Some(Ok(ExitReason::Stopped)),
+                Err(e) => *exit = Some(Err(e)),
+            }
+            if let Some(handler) = engine.handler.as_mut() {
                 if engine.has_changed {
                     let (raw, readlock) = engine.screen.get_ref().get_read_lock();
-                    engine.handler.render(raw);
+                    handler.render(raw);
                     drop(readlock);
-                    engine.has_changed = false;
                 } else {
-                    engine.handler.render_no_update();
+                    handler.render_no_update();
                 }
-                redraw = false;
-                redraw_last_frame = true;
             }
-        });
+            engine.has_changed = false;
+            *redraw = false;
+            *redraw_last_frame = true;
+        }
+        match exit {
+            Some(Ok(_)) => *control_flow = winit::event_loop::ControlFlow::Exit,
+            Some(Err(_)) => *control_flow = winit::event_loop::ControlFlow::ExitWithCode(1),
+            None => {}
+        }
     }
 }
 
@@ -308,11 +407,16 @@ impl EngineWrapper {
 #[allow(clippy::struct_excessive_bools)]
 pub struct Engine {
     /* FRONTEND */
-    /// Main title of the window, Window's full title will be `Title - fps`
+    /// Main title of the window, Window's full title will be `Title - fps` unless it was disabled
+    /// with [`Engine::set_show_fps`]
     /// You can modify it to change the title
     pub title: String,
+    show_fps: bool,
     /// Size of the window, with (x-size,y-size,pixel-size)
     size: (u32, u32, u32),
+    window_size: (u32, u32),
+    scale_mode: ScaleMode,
+    viewport: Rectf,
 
     /* TIME */
     /// Time between current frame and last frame, useful for movement's calculations
@@ -320,24 +424,36 @@ pub struct Engine {
     timer: instant::Instant,
     frame_count: u64,
     frame_timer: f64,
+    fixed_timestep: Option<FixedTimestep>,
+    accumulator: Accumulator,
+    frame_limit: Option<u32>,
 
     /* BACKEND */
     pub(crate) screen: DrawingSprite<Sprite>,
     pub(crate) has_changed: bool,
-    pub(crate) handler: px_backend::Context,
+    /// `None` when the engine is headless
+    pub(crate) handler: Option<px_backend::Context>,
     pub(crate) textsheet_decal: Decal,
-    input_buffer: String,
+    pub(crate) text: TextInput,
     input_toggle: bool,
-    input_cursor: usize,
     input_passthrough: std::collections::HashSet<inputs::Keycodes>,
     ignore_passthrough_char: bool,
+    ignore_next_char: bool,
     finish_input: bool,
-    k_pressed: std::collections::HashSet<inputs::Keycodes>,
+    pub(crate) k_pressed: std::collections::HashSet<inputs::Keycodes>,
     k_held: std::collections::HashSet<inputs::Keycodes>,
     k_released: std::collections::HashSet<inputs::Keycodes>,
-    mouse: Mouse,
+    pub(crate) mouse: Mouse,
+    pub(crate) actions: ActionMap,
+    #[cfg(feature = "gamepad")]
+    pub(crate) gamepads: Gamepads,
+    cursor_grab: CursorGrab,
+    pub(crate) focused: bool,
+    pub(crate) playback: Playback,
+    /// True between the start and the end of a frame
+    pub(crate) in_frame: bool,
     event_loop: Option<winit::event_loop::EventLoop<()>>,
-    window: winit::window::Window,
+    window: Option<winit::window::Window>,
 }
 impl std::fmt::Debug for Engine {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -350,10 +466,39 @@ impl std::fmt::Debug for Engine {
     }
 }
 
+impl Drop for Engine {
+    fn drop(&mut self) {
+        // winit can only create one event loop, it is kept for the next engine
+        if let Some(event_loop) = self.event_loop.take() {
+            EVENT_LOOP.with(|cell| *cell.borrow_mut() = Some(event_loop));
+        }
+    }
+}
+
 impl Engine {
     /// Create a new [`Engine`]
-    async fn new(title: String, size: (u32, u32, u32)) -> Self {
-        let event_loop = winit::event_loop::EventLoop::new();
+    #[allow(clippy::too_many_lines)]
+    async fn new(builder: EngineBuilder) -> Self {
+        let EngineBuilder {
+            title,
+            size,
+            resizable,
+            fullscreen,
+            icon,
+            clear_color,
+            present_mode,
+            show_fps,
+            canvas,
+            scale_mode,
+        } = builder;
+        let icon = icon.map(|icon| {
+            let (raw, _lock) = icon.get_read_lock();
+            winit::window::Icon::from_rgba(raw.to_vec(), icon.width(), icon.height())
+                .expect("Error when creating the window icon")
+        });
+        let event_loop = EVENT_LOOP
+            .with(|cell| cell.borrow_mut().take())
+            .unwrap_or_default();
         let window = winit::window::WindowBuilder::new()
             .with_inner_size(
                 #[allow(clippy::cast_precision_loss)]
@@ -365,17 +510,22 @@ impl Engine {
                 },
             )
             .with_title(&title)
-            .with_resizable(false)
+            .with_resizable(resizable)
+            .with_window_icon(icon)
+            .with_fullscreen(fullscreen.then_some(winit::window::Fullscreen::Borderless(None)))
             .build(&event_loop)
             .expect("Error when constructing window");
         window.set_visible(false);
 
+        // The canvas id is only used on the web
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = canvas;
         #[cfg(target_arch = "wasm32")]
         {
             use winit::platform::web::WindowExtWebSys;
 
-            let canvas = window.canvas();
-            let env = option_env!("PIXEL_ENGINE_CANVAS");
+            let canvas_elem = window.canvas();
+            let env = canvas.as_deref().or(option_env!("PIXEL_ENGINE_CANVAS"));
             match env {
                 Some(id) => {
                     let window_sys = web_sys::window().unwrap();
@@ -385,61 +535,400 @@ impl Engine {
                         .expect("The given ID does not exist")
                         .unwrap();
                     parent_canvas
-                        .append_child(&canvas)
+                        .append_child(&canvas_elem)
                         .expect("Append canvas to HTML body");
                 }
                 None => {
                     let window_sys = web_sys::window().unwrap();
                     let document = window_sys.document().unwrap();
                     let body = document.body().unwrap();
-                    body.append_child(&canvas)
+                    body.append_child(&canvas_elem)
                         .expect("Append canvas to HTML body");
                 }
             }
         }
 
-        let mut handler = px_backend::Context::new(&window, size).await;
+        let mut handler = px_backend::Context::new(&window, size, present_mode).await;
+        handler.set_clear_color(clear_color.to_linear().map(f64::from));
         let screen = DrawingSprite::new(Sprite::new(size.0, size.1));
         let textsheet_decal =
             crate::decals::Decal::new(&mut handler, SmartDrawingTrait::get_textsheet(&screen));
 
-        Engine {
+        let window_size = window.inner_size();
+        let mut engine = Engine {
             /* FRONTEND */
             size,
+            window_size: (window_size.width, window_size.height),
+            scale_mode,
+            viewport: Rectf::new(Vf2d { x: 0.0, y: 0.0 }, Vf2d { x: 0.0, y: 0.0 }),
             title,
+            show_fps,
 
             /* TIME */
             timer: instant::Instant::now(),
             frame_count: 0u64,
             frame_timer: 0f64,
             elapsed: 0f64,
+            fixed_timestep: None,
+            accumulator: Accumulator::default(),
+            frame_limit: None,
             /* BACKEND */
-            handler,
+            handler: Some(handler),
             screen,
             has_changed: true,
             textsheet_decal,
-            input_buffer: String::with_capacity(32),
+            text: TextInput::system(),
             input_toggle: false,
-            input_cursor: 0,
             finish_input: false,
             input_passthrough: std::collections::HashSet::new(),
             ignore_passthrough_char: false,
+            ignore_next_char: false,
             k_pressed: std::collections::HashSet::new(),
             k_held: std::collections::HashSet::new(),
             k_released: std::collections::HashSet::new(),
             mouse: Mouse::new(),
+            actions: ActionMap::new(),
+            #[cfg(feature = "gamepad")]
+            gamepads: Gamepads::system(),
+            cursor_grab: CursorGrab::None,
+            focused: true,
+            playback: Playback::Live,
+            in_frame: false,
             window: {
                 window.set_visible(true);
-                window
+                Some(window)
             },
             event_loop: Some(event_loop),
+        };
+        engine.update_viewport();
+        engine
+    }
+    /// Create an [`Engine`] without a window nor a GPU, only the screen [`Sprite`] is drawn to
+    pub(crate) fn new_headless(title: String, size: (u32, u32)) -> Self {
+        let screen = DrawingSprite::new(Sprite::new(size.0, size.1));
+        let textsheet_decal = Decal::detached(SmartDrawingTrait::get_textsheet(&screen));
+
+        #[allow(clippy::cast_precision_loss)]
+        Engine {
+            /* FRONTEND */
+            size: (size.0, size.1, 1),
+            window_size: size,
+            scale_mode: ScaleMode::default(),
+            viewport: Rectf::new(
+                Vf2d { x: 0.0, y: 0.0 },
+                Vf2d {
+                    x: size.0 as f32,
+                    y: size.1 as f32,
+                },
+            ),
+            title,
+            show_fps: false,
+
+            /* TIME */
+            timer: instant::Instant::now(),
+            frame_count: 0u64,
+            frame_timer: 0f64,
+            elapsed: 0f64,
+            fixed_timestep: None,
+            accumulator: Accumulator::default(),
+            frame_limit: None,
+            /* BACKEND */
+            handler: None,
+            screen,
+            has_changed: true,
+            textsheet_decal,
+            text: TextInput::new(Box::<MemoryClipboard>::default()),
+            input_toggle: false,
+            finish_input: false,
+            input_passthrough: std::collections::HashSet::new(),
+            ignore_passthrough_char: false,
+            ignore_next_char: false,
+            k_pressed: std::collections::HashSet::new(),
+            k_held: std::collections::HashSet::new(),
+            k_released: std::collections::HashSet::new(),
+            mouse: Mouse::new(),
+            actions: ActionMap::new(),
+            #[cfg(feature = "gamepad")]
+            gamepads: Gamepads::new(None),
+            cursor_grab: CursorGrab::None,
+            focused: true,
+            playback: Playback::Live,
+            in_frame: false,
+            window: None,
+            event_loop: None,
+        }
+    }
+
+    fn window(&self) -> &winit::window::Window {
+        self.window
+            .as_ref()
+            .expect("A headless engine doesn't have a window")
+    }
+
+    /// Run one frame of the game, `elapsed` seconds after the last one
+    pub(crate) fn frame<G: crate::Game>(
+        &mut self,
+        game: &mut G,
+        elapsed: f64,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        self.in_frame = true;
+        #[cfg(feature = "gamepad")]
+        for event in self.gamepads.poll() {
+            self.input_event(game, InputEvent::Gamepad(event));
+        }
+        let elapsed = if let Some((recorded, events)) = self.playback.next_frame() {
+            for event in events {
+                self.apply_input(game, event);
+            }
+            recorded
+        } else {
+            // The replay is over, the inputs come from the window again
+            if self.playback.is_replaying() {
+                self.stop_replay();
+            }
+            elapsed
+        };
+        self.playback.record_frame(elapsed);
+        if self.input_toggle {
+            self.text.repeat_keys(elapsed);
+        }
+        if let Some(timestep) = self.fixed_timestep {
+            self.elapsed = timestep.step;
+            for _ in 0..self.accumulator.advance(timestep, elapsed) {
+                if !game.fixed_update(self)? {
+                    return Ok(false);
+                }
+            }
+        }
+        self.elapsed = elapsed;
+        game.update(self)
+    }
+
+    /// Move the inputs of the frame that just ran to their next state and free the dropped decals
+    pub(crate) fn end_frame(&mut self) {
+        for key in &self.k_pressed {
+            self.k_held.insert(*key);
+        }
+        self.k_pressed.clear();
+        self.k_released.clear();
+        self.mouse.buttons.retain(|_, button| {
+            if button.released {
+                button.released = false;
+            }
+            if button.pressed {
+                button.pressed = false;
+                button.held = true;
+            }
+            button.any()
+        });
+        self.mouse.wheel = MouseWheel::None;
+        self.mouse.scroll_lines = (0.0, 0.0);
+        self.mouse.scroll_pixels = (0.0, 0.0);
+        self.mouse.delta = (0.0, 0.0);
+        #[cfg(feature = "gamepad")]
+        self.gamepads.end_frame();
+        crate::decals::DECAL_HANDLER.with(|key| {
+            key.borrow_mut().drain(..).for_each(|decal| {
+                if let Some(handler) = self.handler.as_mut() {
+                    decal.destroy(handler);
+                }
+            });
+        });
+        self.in_frame = false;
+        self.start_playback();
+    }
+
+    /// Handle an input from the window, it is ignored during a replay
+    pub(crate) fn input_event<G: crate::Game>(&mut self, game: &mut G, event: InputEvent) {
+        if !self.playback.is_replaying() {
+            self.apply_input(game, event);
+        }
+    }
+
+    /// Give an input to the engine, and record it if needed
+    fn apply_input<G: crate::Game>(&mut self, game: &mut G, event: InputEvent) {
+        match &event {
+            InputEvent::Key(key, pressed) => self.key_input(game, *key, *pressed),
+            InputEvent::Char(chr) => self.char_input(*chr),
+            InputEvent::MouseButton(button, pressed) => self.mouse_input(*button, *pressed),
+            InputEvent::MouseMove(pos) => self.move_mouse(*pos),
+            InputEvent::MouseMotion(x, y) => {
+                self.mouse.delta.0 += x;
+                self.mouse.delta.1 += y;
+            }
+            InputEvent::MouseInside(inside) => self.mouse.inside = *inside,
+            InputEvent::Wheel(wheel) => self.mouse.wheel = *wheel,
+            InputEvent::ScrollLines(delta) => self.scroll_lines(*delta),
+            InputEvent::ScrollPixels(delta) => self.scroll_pixels(*delta),
+            #[cfg(feature = "gamepad")]
+            InputEvent::Gamepad(event) => self.gamepads.apply(event.clone()),
+        }
+        self.playback.record(event);
+    }
+
+    /// Return the state of the inputs, used to start a recording
+    pub(crate) fn input_state(&mut self) -> InputState {
+        let sorted = |keys: &std::collections::HashSet<inputs::Keycodes>| {
+            let mut keys: Vec<_> = keys.iter().copied().collect();
+            keys.sort_unstable();
+            keys
+        };
+        InputState {
+            keys: [
+                sorted(&self.k_pressed),
+                sorted(&self.k_held),
+                sorted(&self.k_released),
+            ],
+            mouse: self.mouse.clone(),
+            text_input: self.input_toggle,
+            text: self.text.state.clone(),
+            clipboard: self.text.clipboard().get_text(),
+            accumulator: self.accumulator.0,
+            #[cfg(feature = "gamepad")]
+            gamepads: self.gamepads.pads.clone(),
+        }
+    }
+
+    /// Restore the state of the inputs, used to start a replay
+    pub(crate) fn set_input_state(&mut self, state: InputState) {
+        let [pressed, held, released] = state.keys;
+        self.k_pressed = pressed.into_iter().collect();
+        self.k_held = held.into_iter().collect();
+        self.k_released = released.into_iter().collect();
+        self.mouse = state.mouse;
+        self.move_mouse(self.mouse.precise_pos.into());
+        self.input_toggle = state.text_input;
+        self.text.state = state.text;
+        self.accumulator.0 = state.accumulator;
+        #[cfg(feature = "gamepad")]
+        {
+            self.gamepads.pads = state.gamepads;
+        }
+    }
+
+    /// Handle a key being pressed or released, in input mode it may edit the input buffer
+    pub(crate) fn key_input<G: crate::Game>(
+        &mut self,
+        game: &mut G,
+        key: inputs::Keycodes,
+        pressed: bool,
+    ) {
+        self.ignore_next_char = false;
+        self.text.modifier(key, pressed);
+        if !pressed {
+            self.text.key_released(key);
+        }
+        if !self.input_toggle {
+            self.set_key(key, pressed);
+        } else if pressed {
+            match self.text.key_pressed(key) {
+                KeyAction::Submit(input) => {
+                    self.finish_input = true;
+                    game.receive_input(self, input);
+                }
+                KeyAction::Cancel => self.input_toggle = false,
+                KeyAction::None => {}
+            }
+        }
+        if self.input_passthrough.contains(&key) {
+            self.ignore_next_char = true;
+            self.set_key(key, pressed);
+        }
+    }
+
+    fn set_key(&mut self, key: inputs::Keycodes, pressed: bool) {
+        if !pressed {
+            self.k_pressed.remove(&key);
+            self.k_held.remove(&key);
+            self.k_released.insert(key);
+        } else if !self.k_held.contains(&key) {
+            self.k_pressed.insert(key);
         }
     }
-    #[cfg(not(target_arch = "wasm32"))]
 
-    /// Make a Engine but sync
-    fn new_sync(title: String, size: (u32, u32, u32)) -> Self {
-        futures::executor::block_on(Self::new(title, size))
+    /// Handle a typed character, it is only used in input mode
+    pub(crate) fn char_input(&mut self, chr: char) {
+        if self.input_toggle && !(self.ignore_passthrough_char && self.ignore_next_char) {
+            self.text.insert_str(chr.encode_utf8(&mut [0; 4]));
+        }
+    }
+
+    /// Handle the window being resized to `size` physical pixels
+    pub(crate) fn resize_window(&mut self, size: (u32, u32)) {
+        self.window_size = size;
+        if let Some(handler) = self.handler.as_mut() {
+            handler.resize(size.0, size.1);
+        }
+        self.update_viewport();
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn update_viewport(&mut self) {
+        let window = Vf2d {
+            x: self.window_size.0 as f32,
+            y: self.window_size.1 as f32,
+        };
+        if window.x <= 0.0 || window.y <= 0.0 {
+            return;
+        }
+        let screen = self.size().cast_f32();
+        let fit = (window.x / screen.x).min(window.y / screen.y);
+        let size = match self.scale_mode {
+            ScaleMode::Integer if fit >= 1.0 => screen * fit.floor(),
+            ScaleMode::Integer | ScaleMode::Fit => screen * fit,
+            ScaleMode::Stretch => window,
+        };
+        self.viewport = Rectf::new(((window - size) / 2.0).floor(), size);
+        if let Some(handler) = self.handler.as_mut() {
+            handler.set_viewport(Some((
+                self.viewport.pos.x,
+                self.viewport.pos.y,
+                self.viewport.size.x,
+                self.viewport.size.y,
+            )));
+        }
+    }
+
+    /// Handle the cursor moving to `pos`, in pixels of the screen
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub(crate) fn move_mouse(&mut self, pos: Vf2d) {
+        self.mouse.precise_pos = (pos.x, pos.y);
+        let max = self.size().cast_f32() - Vf2d { x: 1.0, y: 1.0 };
+        self.mouse.pos = (
+            pos.x.floor().clamp(0.0, max.x) as u32,
+            pos.y.floor().clamp(0.0, max.y) as u32,
+        );
+    }
+
+    /// Handle the mouse wheel scrolling by `delta` lines
+    pub(crate) fn scroll_lines(&mut self, delta: Vf2d) {
+        self.mouse.scroll_lines.0 += delta.x;
+        self.mouse.scroll_lines.1 += delta.y;
+        self.mouse.wheel = MouseWheel::from_delta(delta);
+    }
+
+    /// Handle the mouse wheel or the touchpad scrolling by `delta` pixels
+    pub(crate) fn scroll_pixels(&mut self, delta: Vf2d) {
+        self.mouse.scroll_pixels.0 += f64::from(delta.x);
+        self.mouse.scroll_pixels.1 += f64::from(delta.y);
+        self.mouse.wheel = MouseWheel::from_delta(delta);
+    }
+
+    /// Handle a mouse button being pressed or released
+    pub(crate) fn mouse_input(&mut self, btn: MouseBtn, pressed: bool) {
+        let button = self.mouse.buttons.entry(btn).or_insert_with(Input::default);
+        if pressed {
+            button.pressed = true;
+        } else {
+            button.released = true;
+            button.held = false;
+        }
+    }
+
+    pub(crate) fn draw_decal_instance(&mut self, decal: px_backend::decals::DecalInstances) {
+        if let Some(handler) = self.handler.as_mut() {
+            handler.draw_decal_instance(decal);
+        }
     }
     /// Return the current Target size in pixel
     #[inline]
@@ -448,11 +937,191 @@ impl Engine {
     }
 
     /// Return the pixel scale factor
+    ///
+    /// This is the scale used to size the window, when the window is resized the screen may be
+    /// drawn with another scale, see [`Engine::viewport`]
     #[inline]
     pub fn scale(&self) -> u32 {
         self.size.2
     }
 
+    /// Return the size of the window in physical pixels
+    #[inline]
+    pub fn window_size(&self) -> Vu2d {
+        self.window_size.into()
+    }
+
+    /// Return the part of the window where the screen is drawn, in physical pixels
+    ///
+    /// The rest of the window is filled with the clear color
+    #[inline]
+    pub fn viewport(&self) -> Rectf {
+        self.viewport
+    }
+
+    /// Convert a position in physical pixels of the window to a position on the screen
+    ///
+    /// The result is outside of the screen if the position is on the letterbox bars
+    pub fn window_to_screen(&self, pos: impl Into<Vf2d>) -> Vf2d {
+        let pos = pos.into() - self.viewport.pos;
+        pos / self.viewport.size * self.size().cast_f32()
+    }
+
+    /// Change the size of the screen, the screen is cleared
+    ///
+    /// Unless it is fullscreen, the window is resized to fit the new screen at the current
+    /// [scale](Engine::set_scale). A width or height of `0` is raised to `1`
+    pub fn set_resolution(&mut self, size: impl Into<Vu2d>) {
+        let size = size.into();
+        let size = Vu2d {
+            x: size.x.max(1),
+            y: size.y.max(1),
+        };
+        let mut screen = DrawingSprite::new(Sprite::new(size.x, size.y));
+        screen.set_pixel_mode(self.screen.get_pixel_mode());
+        screen.set_blend_factor(self.screen.get_blend_factor());
+        self.screen = screen;
+        self.size = (size.x, size.y, self.size.2);
+        self.has_changed = true;
+        if let Some(handler) = self.handler.as_mut() {
+            let (raw, readlock) = self.screen.get_ref().get_read_lock();
+            handler.resize_screen(raw, (size.x, size.y));
+            drop(readlock);
+        }
+        self.mouse.pos = (
+            self.mouse.pos.0.min(size.x.saturating_sub(1)),
+            self.mouse.pos.1.min(size.y.saturating_sub(1)),
+        );
+        self.fit_window();
+        self.update_viewport();
+    }
+
+    /// Change the pixel scale factor, the window is resized to fit the screen at the new scale
+    /// unless it is fullscreen
+    pub fn set_scale(&mut self, scale: u32) {
+        self.size.2 = scale.max(1);
+        self.fit_window();
+    }
+
+    /// Resize the window to the size of the screen multiplied by the scale
+    fn fit_window(&mut self) {
+        let size = (self.size.0 * self.size.2, self.size.1 * self.size.2);
+        match self.window.as_ref() {
+            Some(window) if window.fullscreen().is_none() => {
+                // The viewport is updated once the window sends the `Resized` event
+                window.set_inner_size(winit::dpi::PhysicalSize::new(size.0, size.1));
+            }
+            Some(_) => {}
+            None => self.resize_window(size),
+        }
+    }
+
+    /// Switch between borderless fullscreen on the current monitor and windowed mode
+    ///
+    /// This does nothing for a headless engine
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        if let Some(window) = self.window.as_ref() {
+            window.set_fullscreen(
+                fullscreen.then(|| winit::window::Fullscreen::Borderless(window.current_monitor())),
+            );
+        }
+    }
+
+    /// Return true if the window is fullscreen
+    #[inline]
+    pub fn is_fullscreen(&self) -> bool {
+        self.window
+            .as_ref()
+            .is_some_and(|window| window.fullscreen().is_some())
+    }
+
+    /// Change how the screen is scaled to fill the window
+    pub fn set_scale_mode(&mut self, mode: ScaleMode) {
+        self.scale_mode = mode;
+        self.update_viewport();
+    }
+
+    /// Return how the screen is scaled to fill the window
+    #[inline]
+    pub fn scale_mode(&self) -> ScaleMode {
+        self.scale_mode
+    }
+
+    /// Switch between the fixed timestep mode and the default variable mode (`None`)
+    ///
+    /// In fixed timestep mode [`Game::fixed_update`](crate::Game::fixed_update) is called at the
+    /// rate of the given [`FixedTimestep`] and [`Game::update`](crate::Game::update) is still
+    /// called once per frame, which is where the game should be drawn using
+    /// [`Engine::interpolation_alpha`]
+    pub fn set_fixed_timestep(&mut self, timestep: Option<FixedTimestep>) {
+        self.fixed_timestep = timestep;
+        self.accumulator = Accumulator::default();
+    }
+
+    /// Return the current [`FixedTimestep`], `None` in variable mode
+    #[inline]
+    pub fn fixed_timestep(&self) -> Option<FixedTimestep> {
+        self.fixed_timestep
+    }
+
+    /// Return how far the current frame is between the last fixed step and the next one, from 0
+    /// to 1
+    ///
+    /// Drawing the state of the game interpolated between the last two steps by this amount
+    /// gives smooth movement even when the steps and the frames aren't in sync. This is always
+    /// 1 in variable mode.
+    #[inline]
+    pub fn interpolation_alpha(&self) -> f64 {
+        self.fixed_timestep
+            .map_or(1.0, |timestep| self.accumulator.alpha(timestep))
+    }
+
+    /// Change how the frames are shown on the screen, return the mode that is really used
+    ///
+    /// [`PresentMode::Mailbox`] falls back to [`PresentMode::Vsync`] when it isn't supported. A
+    /// headless engine doesn't show its frames so this does nothing
+    pub fn set_present_mode(&mut self, mode: PresentMode) -> PresentMode {
+        self.handler
+            .as_mut()
+            .map_or(mode, |handler| handler.set_present_mode(mode))
+    }
+
+    /// Return the current [`PresentMode`]
+    #[inline]
+    pub fn present_mode(&self) -> PresentMode {
+        self.handler
+            .as_ref()
+            .map_or_else(PresentMode::default, px_backend::Context::present_mode)
+    }
+
+    /// Set the color drawn behind the screen, visible through its transparent pixels
+    pub fn set_clear_color(&mut self, color: px_draw::graphics::Color) {
+        if let Some(handler) = self.handler.as_mut() {
+            handler.set_clear_color(color.to_linear().map(f64::from));
+        }
+    }
+
+    /// Show the frame rate in the window's title as `Title - fps`
+    #[inline]
+    pub fn set_show_fps(&mut self, show_fps: bool) {
+        self.show_fps = show_fps;
+    }
+
+    /// Limit the number of frames per second, `None` removes the limit
+    ///
+    /// The engine sleeps then busy waits before each frame to not go faster than `fps`, which
+    /// also works when vsync isn't available. This is ignored on the web where the browser
+    /// already paces the frames, and by a headless engine where frames are stepped by hand
+    pub fn set_frame_limit(&mut self, fps: Option<u32>) {
+        self.frame_limit = fps.filter(|&fps| fps > 0);
+    }
+
+    /// Return the current frame limit, see [`Engine::set_frame_limit`]
+    #[inline]
+    pub fn frame_limit(&self) -> Option<u32> {
+        self.frame_limit
+    }
+
     /// Get The status of a key
     #[inline]
     pub fn get_key(&self, keycode: inputs::Keycodes) -> Input {
@@ -464,11 +1133,11 @@ impl Engine {
     }
     /// Get the status of a Mouse Button
     pub fn get_mouse_btn(&self, btn: MouseBtn) -> Input {
-        self.mouse.buttons[match btn {
-            MouseBtn::Left => 0,
-            MouseBtn::Right => 1,
-            MouseBtn::Middle => 2,
-        }]
+        self.mouse
+            .buttons
+            .get(&btn)
+            .copied()
+            .unwrap_or(Input::default())
     }
 
     /// Get the mouse location (in pixel) on the screen
@@ -477,6 +1146,115 @@ impl Engine {
     pub fn get_mouse_location(&self) -> Vu2d {
         self.mouse.pos.into()
     }
+    /// Get the mouse location on the screen, with the position inside of the pixel
+    ///
+    /// Unlike [`Engine::get_mouse_location`] this isn't clamped to the screen, it is negative or
+    /// bigger than the screen when the cursor is on the letterbox bars
+    #[inline]
+    pub fn get_mouse_position(&self) -> Vf2d {
+        self.mouse.precise_pos.into()
+    }
+
+    /// Get how much the mouse moved during the frame
+    ///
+    /// This is the raw motion of the mouse, it isn't in pixels and isn't affected by the cursor
+    /// reaching the border of the screen or being [locked](CursorGrab::Locked), which makes it the
+    /// right value for mouse look. It is only recorded while the window has the focus
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn get_mouse_delta(&self) -> Vf2d {
+        Vf2d {
+            x: self.mouse.delta.0 as f32,
+            y: self.mouse.delta.1 as f32,
+        }
+    }
+
+    /// Return true if the cursor is inside of the window
+    #[inline]
+    pub fn is_mouse_inside(&self) -> bool {
+        self.mouse.inside
+    }
+
+    /// Keep the cursor inside of the window, return the grab that is really used
+    ///
+    /// Not every platform supports both [`CursorGrab::Confined`] and [`CursorGrab::Locked`], when
+    /// the requested one isn't supported the other one is used, and if neither is
+    /// [`CursorGrab::None`] is returned. A headless engine has no cursor, it always returns
+    /// [`CursorGrab::None`]
+    pub fn set_cursor_grab(&mut self, grab: CursorGrab) -> CursorGrab {
+        use winit::window::CursorGrabMode;
+        let Some(window) = self.window.as_ref() else {
+            return CursorGrab::None;
+        };
+        let modes: &[(CursorGrab, CursorGrabMode)] = match grab {
+            CursorGrab::None => &[(CursorGrab::None, CursorGrabMode::None)],
+            CursorGrab::Confined => &[
+                (CursorGrab::Confined, CursorGrabMode::Confined),
+                (CursorGrab::Locked, CursorGrabMode::Locked),
+            ],
+            CursorGrab::Locked => &[
+                (CursorGrab::Locked, CursorGrabMode::Locked),
+                (CursorGrab::Confined, CursorGrabMode::Confined),
+            ],
+        };
+        self.cursor_grab = modes
+            .iter()
+            .find(|(_, mode)| window.set_cursor_grab(*mode).is_ok())
+            .map_or(CursorGrab::None, |(grab, _)| *grab);
+        self.cursor_grab
+    }
+
+    /// Return how the cursor is kept inside of the window
+    #[inline]
+    pub fn cursor_grab(&self) -> CursorGrab {
+        self.cursor_grab
+    }
+
+    /// Return true if the window has the keyboard focus
+    #[inline]
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Show or hide the cursor when it is over the window
+    #[inline]
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        if let Some(window) = self.window.as_ref() {
+            window.set_cursor_visible(visible);
+        }
+    }
+
+    /// Get how much the mouse wheel scrolled during the frame, in lines
+    ///
+    /// Scrolling reported in pixels, by touchpads for example, is converted with
+    /// [`PIXELS_PER_LINE`](inputs::PIXELS_PER_LINE). A positive `y` is scrolling up (away from the
+    /// user), a positive `x` is scrolling right
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn get_mouse_scroll(&self) -> Vf2d {
+        Vf2d {
+            x: self.mouse.scroll_lines.0
+                + (self.mouse.scroll_pixels.0 / inputs::PIXELS_PER_LINE) as f32,
+            y: self.mouse.scroll_lines.1
+                + (self.mouse.scroll_pixels.1 / inputs::PIXELS_PER_LINE) as f32,
+        }
+    }
+
+    /// Get the scrolling reported in lines during the frame, usually by mouse wheels
+    #[inline]
+    pub fn get_mouse_scroll_lines(&self) -> Vf2d {
+        self.mouse.scroll_lines.into()
+    }
+
+    /// Get the scrolling reported in pixels during the frame, usually by touchpads
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn get_mouse_scroll_pixels(&self) -> Vf2d {
+        Vf2d {
+            x: self.mouse.scroll_pixels.0 as f32,
+            y: self.mouse.scroll_pixels.1 as f32,
+        }
+    }
+
     /// Get the scroll wheel direction (If Any) during the frame
     #[inline]
     pub fn get_mouse_wheel(&self) -> MouseWheel {
@@ -498,63 +1276,83 @@ impl Engine {
         self.k_released.clone()
     }
 
+    /// Split the screen into horizontal bands of `band_height` rows that can be drawn on from
+    /// different threads
+    ///
+    /// See [`Sprite::split_rows`]
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if a part of the screen is still borrowed by a leaked
+    /// [`SpriteMutRef`]
+    pub fn split_screen_rows(
+        &mut self,
+        band_height: u32,
+    ) -> Result<Vec<SpriteMutRef<'_>>, OverlappingError> {
+        self.has_changed = true;
+        self.screen.get_ref().split_rows(band_height)
+    }
+
+    /// Split the screen into tiles of `tile_size` that can be drawn on from different threads
+    ///
+    /// See [`Sprite::split_tiles`]
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if a part of the screen is still borrowed by a leaked
+    /// [`SpriteMutRef`]
+    pub fn split_screen_tiles(
+        &mut self,
+        tile_size: impl Into<Vu2d>,
+    ) -> Result<Vec<SpriteMutRef<'_>>, OverlappingError> {
+        self.has_changed = true;
+        self.screen.get_ref().split_tiles(tile_size.into())
+    }
+
     /// Create a GPU version of [`Sprite`]
+    ///
+    /// A [headless](super::Headless) engine has no GPU, the decal is still created but drawing it
+    /// does nothing
     #[inline]
     pub fn create_decal(&mut self, sprite: &Sprite) -> Decal {
-        Decal::new(&mut self.handler, sprite)
+        match self.handler.as_mut() {
+            Some(handler) => Decal::new(handler, sprite),
+            None => Decal::detached(sprite),
+        }
     }
 
     /// Will clear the input buffer and set the cursor to 0
     #[inline]
     pub fn clear_input_buffer(&mut self) {
-        self.input_buffer.clear();
-        self.input_cursor = 0;
+        self.text.clear();
     }
 
     /// Set the input buffer to the desired value and put the input cursor at the end of the text
     ///
-    /// # Warning
-    ///
-    /// The value must be an ascii string
+    /// Like typed text, the control characters, the characters rejected by the
+    /// [filter](Engine::set_input_filter) and the ones over the
+    /// [maximum length](Engine::set_input_max_len) are dropped
     pub fn set_input_buffer(&mut self, value: impl AsRef<str>) {
-        let value = value.as_ref();
-        if value.is_ascii() {
-            self.clear_input_buffer();
-            self.input_buffer.push_str(value.as_ref());
-            self.input_cursor = self.input_buffer.len();
-        } else if cfg!(debug_assertions) {
-            eprintln!("You can only pass valid ascii string into the input");
-        }
+        self.text.clear();
+        self.text.insert_str(value.as_ref());
     }
 
-    /// Insert the given string into the input buffer at the input cursor
+    /// Insert the given string into the input buffer at the input cursor, in place of the
+    /// selection
     ///
-    /// # Warning
-    ///
-    /// if the string is not only ASCII characters it will silently do nothing
+    /// The characters are filtered like in [`Engine::set_input_buffer`]
     pub fn insert_input_buffer(&mut self, value: impl AsRef<str>) {
-        let value = value.as_ref();
-        if value.is_ascii() {
-            self.input_buffer.insert_str(self.input_cursor, value);
-            self.input_cursor += value.len();
-        } else if cfg!(debug_assertions) {
-            eprintln!("You can only pass valid ascii string into the input");
-        }
+        self.text.insert_str(value.as_ref());
     }
 
-    /// Append the given string onto the input buffer
+    /// Append the given string onto the input buffer and put the input cursor at the end of the
+    /// text
     ///
-    /// # Warning
-    ///
-    /// if the string is not only ASCII characters it will silently do nothing
+    /// The characters are filtered like in [`Engine::set_input_buffer`]
     pub fn append_input_buffer(&mut self, value: impl AsRef<str>) {
-        let value = value.as_ref();
-        if value.is_ascii() {
-            self.input_buffer.push_str(value);
-            self.input_cursor += value.len();
-        } else if cfg!(debug_assertions) {
-            eprintln!("You can only pass valid ascii string into the input");
-        }
+        self.text.state.cursor = self.text.state.text.len();
+        self.text.state.anchor = None;
+        self.text.insert_str(value.as_ref());
     }
 
     /// Add keys onto the input pass through list
@@ -588,13 +1386,13 @@ impl Engine {
     /// Return a view into the input buffer
     #[inline]
     pub fn get_input_buffer(&self) -> &str {
-        &self.input_buffer
+        &self.text.state.text
     }
 
-    /// Return the input cursor
+    /// Return the position of the input cursor, in characters
     #[inline]
     pub fn get_input_cursor(&self) -> usize {
-        self.input_cursor
+        self.text.char_pos(self.text.state.cursor)
     }
 
     /// Switch to text input mode, return true if it wasn't in this mode before
@@ -606,8 +1404,7 @@ impl Engine {
     /// This will clear the input buffer, reset the input cursor and stop the input mode
     #[inline]
     pub fn force_stop_input_mode(&mut self) {
-        self.input_buffer.clear();
-        self.input_cursor = 0;
+        self.text.clear();
         self.input_toggle = false;
     }
 
@@ -622,6 +1419,6 @@ impl Engine {
     #[inline]
     pub fn end_input_mode(&mut self) -> &str {
         self.input_toggle = false;
-        self.input_buffer.as_str()
+        &self.text.state.text
     }
 }
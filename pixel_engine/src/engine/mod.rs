@@ -1,20 +1,40 @@
 #![allow(clippy::module_name_repetitions)]
+pub use px_draw::color;
+pub use px_draw::filters;
+pub use px_draw::geometry;
 pub use px_draw::graphics;
+pub use px_draw::palette;
+pub use px_draw::quantize;
+pub use px_draw::raycast;
+pub use px_draw::rect;
+pub use px_draw::sampler;
+pub use px_draw::shader;
 pub use px_draw::vector2;
 /// A collection of traits used to draw things
 pub mod traits;
 
 /// A Gpu-located spite
 pub mod decals;
+pub mod actions;
+mod builder;
 mod game;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+mod headless;
 /// User Input module
 pub mod inputs;
 mod logic;
+pub mod replay;
 mod screen;
+pub mod text_input;
+pub mod timestep;
+pub use builder::EngineBuilder;
 pub use game::Game;
+pub use headless::Headless;
 pub use graphics::{Color, PixelMode, Sprite};
 
-pub use logic::{Engine, EngineWrapper};
+pub use logic::{Engine, EngineWrapper, ExitReason, ScaleMode};
+pub use px_backend::PresentMode;
 
 /// Takes a future and run it in the context of the engine
 /// This is usefull when targeting wasm32 because we can't use the futures' `block_on` method
@@ -0,0 +1,1066 @@
+//! Input recording and replay
+//!
+//! A [`Recording`] holds the inputs the engine received during every frame, with the
+//! [`elapsed`](Engine::elapsed) time of the frame. Replaying it feeds the same inputs to the
+//! engine in the same frames, so a game that only depends on its inputs and `elapsed` (no
+//! randomness from the outside, no reading of the clock) runs exactly like it did when it was
+//! recorded. This can be used to reproduce bugs, to record demos or for regression tests with
+//! [`Headless`](crate::Headless).
+//!
+//! The game has to be in the same state when the replay starts as when the recording started,
+//! the state of the inputs at that time is part of the recording. So is the content of the
+//! clipboard: during a replay the text input copies and pastes with a copy of it.
+//!
+//! ```
+//! use pixel_engine::inputs::Keycodes;
+//! # struct Game {
+//! #     x: i32,
+//! # }
+//! # impl pixel_engine::Game for Game {
+//! #     fn create(_: &mut pixel_engine::Engine) -> Result<Self, Box<dyn std::error::Error>> {
+//! #         Ok(Game { x: 0 })
+//! #     }
+//! #     fn update(&mut self, engine: &mut pixel_engine::Engine) -> Result<bool, Box<dyn std::error::Error>> {
+//! #         if engine.get_key(Keycodes::Right).held {
+//! #             self.x += 1;
+//! #         }
+//! #         Ok(true)
+//! #     }
+//! # }
+//!
+//! let mut headless = pixel_engine::Headless::<Game>::new("test", (32, 32)).unwrap();
+//! headless.engine_mut().start_recording();
+//! headless.press_key(Keycodes::Right);
+//! headless.step_n(10, 1.0 / 60.0).unwrap();
+//! let recording = headless.engine_mut().stop_recording().unwrap();
+//! let x = headless.game().x;
+//!
+//! let bytes = recording.to_bytes();
+//! let recording = pixel_engine::replay::Recording::from_bytes(&bytes).unwrap();
+//!
+//! let mut headless = pixel_engine::Headless::<Game>::new("test", (32, 32)).unwrap();
+//! headless.replay(recording).unwrap();
+//! assert_eq!(headless.game().x, x);
+//! ```
+
+#[cfg(feature = "gamepad")]
+use std::collections::BTreeMap;
+
+#[cfg(feature = "gamepad")]
+use super::gamepad::{GamepadAxis, GamepadButton, GamepadEvent, GamepadId, Pad};
+use super::inputs::{Input, Keycodes, Mouse, MouseBtn, MouseWheel};
+use super::text_input::{MemoryClipboard, TextState};
+use crate::vector2::Vf2d;
+use crate::Engine;
+
+/// An input received by the engine
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum InputEvent {
+    Key(Keycodes, bool),
+    Char(char),
+    MouseButton(MouseBtn, bool),
+    /// The cursor moved to this position of the screen
+    MouseMove(Vf2d),
+    /// Raw motion of the mouse
+    MouseMotion(f64, f64),
+    MouseInside(bool),
+    Wheel(MouseWheel),
+    ScrollLines(Vf2d),
+    ScrollPixels(Vf2d),
+    #[cfg(feature = "gamepad")]
+    Gamepad(GamepadEvent),
+}
+
+/// The state of the inputs when a recording starts
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InputState {
+    /// The pressed, held and released keys, sorted
+    pub(crate) keys: [Vec<Keycodes>; 3],
+    pub(crate) mouse: Mouse,
+    pub(crate) text_input: bool,
+    pub(crate) text: TextState,
+    /// Content of the clipboard, the replays use a copy of it instead of the real clipboard
+    pub(crate) clipboard: Option<String>,
+    /// The time not yet used by the fixed timestep
+    pub(crate) accumulator: f64,
+    #[cfg(feature = "gamepad")]
+    pub(crate) gamepads: BTreeMap<GamepadId, Pad>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RecordedFrame {
+    elapsed: f64,
+    events: Vec<InputEvent>,
+}
+
+/// The inputs of a sequence of frames, see the [module documentation](self)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    start: InputState,
+    frames: Vec<RecordedFrame>,
+}
+
+/// What the engine does with the inputs, there is only one per engine
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Default)]
+pub(crate) enum Playback {
+    /// The inputs come from the window
+    #[default]
+    Live,
+    /// The inputs come from the window and are recorded, `start` is `None` until the current
+    /// frame ends
+    Recording {
+        start: Option<InputState>,
+        frames: Vec<RecordedFrame>,
+        /// Inputs received since the last frame
+        events: Vec<InputEvent>,
+    },
+    /// The inputs come from a recording, the inputs of the window are ignored
+    Replaying {
+        recording: Recording,
+        next: usize,
+        /// The inputs before the replay, `None` until the current frame ends
+        live: Option<InputState>,
+    },
+}
+
+impl Playback {
+    /// Save an input that was given to the engine
+    pub(crate) fn record(&mut self, event: InputEvent) {
+        if let Playback::Recording {
+            start: Some(_),
+            events,
+            ..
+        } = self
+        {
+            events.push(event);
+        }
+    }
+
+    /// Save the inputs of the frame that starts
+    pub(crate) fn record_frame(&mut self, elapsed: f64) {
+        if let Playback::Recording {
+            start: Some(_),
+            frames,
+            events,
+        } = self
+        {
+            frames.push(RecordedFrame {
+                elapsed,
+                events: std::mem::take(events),
+            });
+        }
+    }
+
+    /// Return the `elapsed` time and the inputs of the next frame of the replay, `None` when
+    /// not replaying or at the end of the replay
+    pub(crate) fn next_frame(&mut self) -> Option<(f64, Vec<InputEvent>)> {
+        match self {
+            Playback::Replaying {
+                recording,
+                next,
+                live: Some(_),
+            } => {
+                let frame = recording.frames.get(*next)?;
+                *next += 1;
+                Some((frame.elapsed, frame.events.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_replaying(&self) -> bool {
+        matches!(self, Playback::Replaying { .. })
+    }
+}
+
+impl Recording {
+    /// Return the number of frames
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Return true if there isn't any frame
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Return the duration of the recording in seconds
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        self.frames.iter().map(|frame| frame.elapsed).sum()
+    }
+
+    /// Encode the recording
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(MAGIC.len() + 64 + self.frames.len() * 10);
+        out.extend_from_slice(MAGIC);
+        write_state(&mut out, &self.start);
+        write_varint(&mut out, self.frames.len() as u64);
+        for frame in &self.frames {
+            out.extend_from_slice(&frame.elapsed.to_le_bytes());
+            write_varint(&mut out, frame.events.len() as u64);
+            for event in &frame.events {
+                write_event(&mut out, event);
+            }
+        }
+        out
+    }
+
+    /// Decode a recording made by [`Recording::to_bytes`]
+    ///
+    /// # Errors
+    ///
+    /// If the data isn't a valid recording, or if it contains gamepad inputs and the `gamepad`
+    /// feature is disabled
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err("Not an input recording".to_string());
+        }
+        let start = reader.state()?;
+        let count = reader.len()?;
+        let mut frames = Vec::with_capacity(count.min(bytes.len() / 9));
+        for _ in 0..count {
+            let elapsed = reader.f64()?;
+            let events = (0..reader.len()?)
+                .map(|_| reader.event())
+                .collect::<Result<_, _>>()?;
+            frames.push(RecordedFrame { elapsed, events });
+        }
+        if reader.pos != bytes.len() {
+            return Err("Unexpected data at the end of the recording".to_string());
+        }
+        Ok(Self { start, frames })
+    }
+
+    /// Save the recording to a file
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be written
+    pub fn save_to_file<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+
+    /// Load a recording saved with [`Recording::save_to_file`]
+    ///
+    /// # Errors
+    ///
+    /// If the file IO failed or if the file isn't a valid recording
+    pub fn load_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        Self::from_bytes(&std::fs::read(path).map_err(|err| err.to_string())?)
+    }
+}
+
+impl Engine {
+    /// Start recording the inputs, the recording starts with the next frame
+    ///
+    /// A recording or a replay in progress is stopped
+    pub fn start_recording(&mut self) {
+        self.playback = Playback::Recording {
+            start: None,
+            frames: Vec::new(),
+            events: Vec::new(),
+        };
+        if !self.in_frame {
+            self.start_playback();
+        }
+    }
+
+    /// Stop recording and return the recording, `None` if the engine wasn't recording
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        match std::mem::take(&mut self.playback) {
+            Playback::Recording { start, frames, .. } => Some(Recording {
+                start: start.unwrap_or_else(|| self.input_state()),
+                frames,
+            }),
+            playback => {
+                self.playback = playback;
+                None
+            }
+        }
+    }
+
+    /// Return true if the inputs are being recorded
+    #[must_use]
+    pub fn is_recording(&self) -> bool {
+        matches!(self.playback, Playback::Recording { .. })
+    }
+
+    /// Replay a recording, the replay starts with the next frame
+    ///
+    /// During the replay, the inputs come from the recording and the inputs of the window are
+    /// ignored. The [`elapsed`](Engine::elapsed) time of each frame is the one of the
+    /// recording. Once the replay ends, the inputs are released and come from the window again.
+    ///
+    /// A recording or a replay in progress is stopped
+    pub fn start_replay(&mut self, recording: Recording) {
+        self.playback = Playback::Replaying {
+            recording,
+            next: 0,
+            live: None,
+        };
+        if !self.in_frame {
+            self.start_playback();
+        }
+    }
+
+    /// Stop the replay, the inputs are released and come from the window again
+    pub fn stop_replay(&mut self) {
+        let Playback::Replaying { live, .. } = std::mem::take(&mut self.playback) else {
+            return;
+        };
+        let Some(live) = live else {
+            return;
+        };
+        let mut state = self.input_state();
+        state.keys = Default::default();
+        state.mouse.buttons.clear();
+        state.text.modifiers.clear();
+        state.text.repeat = None;
+        // The gamepads connected during the replay are the ones of the recording
+        #[cfg(feature = "gamepad")]
+        {
+            state.gamepads = live.gamepads;
+            for pad in state.gamepads.values_mut() {
+                pad.buttons.clear();
+                pad.down.clear();
+            }
+        }
+        #[cfg(not(feature = "gamepad"))]
+        drop(live);
+        self.set_input_state(state);
+        self.text.replay_clipboard = None;
+    }
+
+    /// Return true while a recording is replayed
+    #[must_use]
+    pub fn is_replaying(&self) -> bool {
+        self.playback.is_replaying()
+    }
+
+    /// Return the number of frames replayed and the number of frames of the recording
+    #[must_use]
+    pub fn replay_progress(&self) -> Option<(usize, usize)> {
+        match &self.playback {
+            Playback::Replaying {
+                recording, next, ..
+            } => Some((*next, recording.len())),
+            _ => None,
+        }
+    }
+
+    /// Take the initial state of a recording or restore the one of a replay, done between two
+    /// frames
+    pub(crate) fn start_playback(&mut self) {
+        match &mut self.playback {
+            Playback::Recording { start: None, .. } => {
+                let state = self.input_state();
+                if let Playback::Recording { start, .. } = &mut self.playback {
+                    *start = Some(state);
+                }
+            }
+            Playback::Replaying {
+                recording,
+                live: None,
+                ..
+            } => {
+                let state = recording.start.clone();
+                let live = self.input_state();
+                if let Playback::Replaying { live: saved, .. } = &mut self.playback {
+                    *saved = Some(live);
+                }
+                self.text.replay_clipboard = Some(MemoryClipboard {
+                    text: state.clipboard.clone(),
+                });
+                self.set_input_state(state);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Start of every recording, the last byte is the version of the format
+const MAGIC: &[u8] = b"PXREC\x01";
+
+/// Every [`Keycodes`], in the order of their declaration so `KEYCODES[key as usize] == key`
+#[rustfmt::skip]
+const KEYCODES: [Keycodes; 163] = {
+    use Keycodes as K;
+    [
+        K::Key1, K::Key2, K::Key3, K::Key4, K::Key5, K::Key6, K::Key7, K::Key8, K::Key9, K::Key0, K::A,
+        K::B, K::C, K::D, K::E, K::F, K::G, K::H, K::I, K::J, K::K, K::L, K::M, K::N, K::O, K::P, K::Q,
+        K::R, K::S, K::T, K::U, K::V, K::W, K::X, K::Y, K::Z, K::Escape, K::F1, K::F2, K::F3, K::F4,
+        K::F5, K::F6, K::F7, K::F8, K::F9, K::F10, K::F11, K::F12, K::F13, K::F14, K::F15, K::F16,
+        K::F17, K::F18, K::F19, K::F20, K::F21, K::F22, K::F23, K::F24, K::Snapshot, K::Scroll,
+        K::Pause, K::Insert, K::Home, K::Delete, K::End, K::PageDown, K::PageUp, K::Left, K::Up,
+        K::Right, K::Down, K::Back, K::Return, K::Space, K::Compose, K::Caret, K::Numlock, K::Numpad0,
+        K::Numpad1, K::Numpad2, K::Numpad3, K::Numpad4, K::Numpad5, K::Numpad6, K::Numpad7, K::Numpad8,
+        K::Numpad9, K::NumpadAdd, K::NumpadDivide, K::NumpadDecimal, K::NumpadComma, K::NumpadEnter,
+        K::NumpadEquals, K::NumpadMultiply, K::NumpadSubtract, K::AbntC1, K::AbntC2, K::Apostrophe,
+        K::Apps, K::Asterisk, K::At, K::Ax, K::Backslash, K::Calculator, K::Capital, K::Colon,
+        K::Comma, K::Convert, K::Equals, K::Grave, K::Kana, K::Kanji, K::LAlt, K::LBracket,
+        K::LControl, K::LShift, K::LWin, K::Mail, K::MediaSelect, K::MediaStop, K::Minus, K::Mute,
+        K::MyComputer, K::NavigateForward, K::NavigateBackward, K::NextTrack, K::NoConvert, K::OEM102,
+        K::Period, K::PlayPause, K::Plus, K::Power, K::PrevTrack, K::RAlt, K::RBracket, K::RControl,
+        K::RShift, K::RWin, K::Semicolon, K::Slash, K::Sleep, K::Stop, K::Sysrq, K::Tab, K::Underline,
+        K::Unlabeled, K::VolumeDown, K::VolumeUp, K::Wake, K::WebBack, K::WebFavorites, K::WebForward,
+        K::WebHome, K::WebRefresh, K::WebSearch, K::WebStop, K::Yen, K::Copy, K::Paste, K::Cut,
+    ]
+};
+
+#[cfg(not(feature = "gamepad"))]
+const NO_GAMEPAD: &str = "The recording has gamepad inputs, enable the `gamepad` feature";
+
+const MOUSE_WHEELS: [MouseWheel; 5] = [
+    MouseWheel::None,
+    MouseWheel::Up,
+    MouseWheel::Down,
+    MouseWheel::Right,
+    MouseWheel::Left,
+];
+
+#[cfg(feature = "gamepad")]
+const GAMEPAD_BUTTONS: [GamepadButton; 17] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::LeftBumper,
+    GamepadButton::RightBumper,
+    GamepadButton::LeftTrigger,
+    GamepadButton::RightTrigger,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::Mode,
+    GamepadButton::LeftStick,
+    GamepadButton::RightStick,
+    GamepadButton::DPadUp,
+    GamepadButton::DPadDown,
+    GamepadButton::DPadLeft,
+    GamepadButton::DPadRight,
+];
+
+#[cfg(feature = "gamepad")]
+const GAMEPAD_AXES: [GamepadAxis; 6] = [
+    GamepadAxis::LeftStickX,
+    GamepadAxis::LeftStickY,
+    GamepadAxis::RightStickX,
+    GamepadAxis::RightStickY,
+    GamepadAxis::LeftTrigger,
+    GamepadAxis::RightTrigger,
+];
+
+/// Write an unsigned LEB128 integer
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        #[allow(clippy::cast_possible_truncation)]
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    out.push(value as u8);
+}
+
+fn write_str(out: &mut Vec<u8>, value: &str) {
+    write_varint(out, value.len() as u64);
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn write_vf2d(out: &mut Vec<u8>, value: Vf2d) {
+    out.extend_from_slice(&value.x.to_le_bytes());
+    out.extend_from_slice(&value.y.to_le_bytes());
+}
+
+fn write_f64_pair(out: &mut Vec<u8>, value: (f64, f64)) {
+    out.extend_from_slice(&value.0.to_le_bytes());
+    out.extend_from_slice(&value.1.to_le_bytes());
+}
+
+fn mouse_btn_code(button: MouseBtn) -> u64 {
+    match button {
+        MouseBtn::Left => 0,
+        MouseBtn::Right => 1,
+        MouseBtn::Middle => 2,
+        MouseBtn::Back => 3,
+        MouseBtn::Forward => 4,
+        MouseBtn::Other(other) => 5 + u64::from(other),
+    }
+}
+
+/// Pack an [`Input`] in the 3 lowest bits of a byte
+fn input_bits(input: Input) -> u8 {
+    u8::from(input.pressed) | u8::from(input.held) << 1 | u8::from(input.released) << 2
+}
+
+/// Write `None` as 0 and `Some(value)` as 1 followed by the value
+fn write_option_str(out: &mut Vec<u8>, value: Option<&str>) {
+    out.push(u8::from(value.is_some()));
+    if let Some(value) = value {
+        write_str(out, value);
+    }
+}
+
+fn write_text_state(out: &mut Vec<u8>, state: &TextState) {
+    write_str(out, &state.text);
+    write_varint(out, state.cursor as u64);
+    // Positions are shifted by one so 0 means `None`
+    write_varint(out, state.anchor.map_or(0, |anchor| anchor as u64 + 1));
+    write_varint(out, state.history.len() as u64);
+    for line in &state.history {
+        write_str(out, line);
+    }
+    match &state.browsing {
+        Some((index, draft)) => {
+            write_varint(out, *index as u64 + 1);
+            write_str(out, draft);
+        }
+        None => write_varint(out, 0),
+    }
+    write_varint(out, state.modifiers.len() as u64);
+    for key in &state.modifiers {
+        write_varint(out, *key as u64);
+    }
+    match state.repeat {
+        Some((key, timer)) => {
+            write_varint(out, key as u64 + 1);
+            out.extend_from_slice(&timer.to_le_bytes());
+        }
+        None => write_varint(out, 0),
+    }
+}
+
+fn write_state(out: &mut Vec<u8>, state: &InputState) {
+    for keys in &state.keys {
+        write_varint(out, keys.len() as u64);
+        for key in keys {
+            write_varint(out, *key as u64);
+        }
+    }
+    let mouse = &state.mouse;
+    let mut buttons: Vec<_> = mouse
+        .buttons
+        .iter()
+        .map(|(button, input)| (mouse_btn_code(*button), input_bits(*input)))
+        .collect();
+    buttons.sort_unstable();
+    write_varint(out, buttons.len() as u64);
+    for (button, bits) in buttons {
+        write_varint(out, button);
+        out.push(bits);
+    }
+    write_vf2d(out, mouse.precise_pos.into());
+    write_f64_pair(out, mouse.delta);
+    out.push(u8::from(mouse.inside));
+    out.push(mouse.wheel as u8);
+    write_vf2d(out, mouse.scroll_lines.into());
+    write_f64_pair(out, mouse.scroll_pixels);
+    out.push(u8::from(state.text_input));
+    write_text_state(out, &state.text);
+    write_option_str(out, state.clipboard.as_deref());
+    out.extend_from_slice(&state.accumulator.to_le_bytes());
+    #[cfg(feature = "gamepad")]
+    {
+        write_varint(out, state.gamepads.len() as u64);
+        for (id, pad) in &state.gamepads {
+            write_varint(out, id.0 as u64);
+            write_str(out, &pad.name);
+            let mut buttons: Vec<_> = pad
+                .buttons
+                .iter()
+                .map(|(button, input)| (*button as u8, input_bits(*input)))
+                .collect();
+            buttons.sort_unstable();
+            write_varint(out, buttons.len() as u64);
+            for (button, bits) in buttons {
+                out.push(button | (bits << 5));
+            }
+            let mut down: Vec<_> = pad.down.iter().map(|button| *button as u8).collect();
+            down.sort_unstable();
+            write_varint(out, down.len() as u64);
+            out.extend_from_slice(&down);
+            let mut axes: Vec<_> = pad.axes.iter().map(|(a, v)| (*a as u8, *v)).collect();
+            axes.sort_unstable_by_key(|(axis, _)| *axis);
+            write_varint(out, axes.len() as u64);
+            for (axis, value) in axes {
+                out.push(axis);
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+    #[cfg(not(feature = "gamepad"))]
+    write_varint(out, 0);
+}
+
+fn write_event(out: &mut Vec<u8>, event: &InputEvent) {
+    match *event {
+        InputEvent::Key(key, pressed) => {
+            out.push(u8::from(!pressed));
+            write_varint(out, key as u64);
+        }
+        InputEvent::Char(chr) => {
+            out.push(2);
+            write_varint(out, u64::from(chr));
+        }
+        InputEvent::MouseButton(button, pressed) => {
+            out.push(3 + u8::from(!pressed));
+            write_varint(out, mouse_btn_code(button));
+        }
+        InputEvent::MouseMove(pos) => {
+            out.push(5);
+            write_vf2d(out, pos);
+        }
+        InputEvent::MouseMotion(x, y) => {
+            out.push(6);
+            write_f64_pair(out, (x, y));
+        }
+        InputEvent::MouseInside(inside) => {
+            out.push(7);
+            out.push(u8::from(inside));
+        }
+        InputEvent::Wheel(wheel) => {
+            out.push(8);
+            out.push(wheel as u8);
+        }
+        InputEvent::ScrollLines(delta) => {
+            out.push(9);
+            write_vf2d(out, delta);
+        }
+        InputEvent::ScrollPixels(delta) => {
+            out.push(10);
+            write_vf2d(out, delta);
+        }
+        #[cfg(feature = "gamepad")]
+        InputEvent::Gamepad(ref event) => {
+            out.push(11);
+            match *event {
+                GamepadEvent::Connected(id, ref name) => {
+                    out.push(0);
+                    write_varint(out, id.0 as u64);
+                    write_str(out, name);
+                }
+                GamepadEvent::Disconnected(id) => {
+                    out.push(1);
+                    write_varint(out, id.0 as u64);
+                }
+                GamepadEvent::ButtonPressed(id, button) => {
+                    out.push(2);
+                    write_varint(out, id.0 as u64);
+                    out.push(button as u8);
+                }
+                GamepadEvent::ButtonReleased(id, button) => {
+                    out.push(3);
+                    write_varint(out, id.0 as u64);
+                    out.push(button as u8);
+                }
+                GamepadEvent::AxisChanged(id, axis, value) => {
+                    out.push(4);
+                    write_varint(out, id.0 as u64);
+                    out.push(axis as u8);
+                    out.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos.saturating_add(len))
+            .ok_or_else(|| "Unexpected end of the recording".to_string())?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool, String> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err("Invalid boolean".to_string()),
+        }
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Invalid integer".to_string())
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        usize::try_from(self.varint()?).map_err(|_| "Invalid length".to_string())
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f64(&mut self) -> Result<f64, String> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn vf2d(&mut self) -> Result<Vf2d, String> {
+        Ok(Vf2d {
+            x: self.f32()?,
+            y: self.f32()?,
+        })
+    }
+
+    fn f64_pair(&mut self) -> Result<(f64, f64), String> {
+        Ok((self.f64()?, self.f64()?))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|err| err.to_string())
+    }
+
+    fn key(&mut self) -> Result<Keycodes, String> {
+        usize::try_from(self.varint()?)
+            .ok()
+            .and_then(|code| KEYCODES.get(code).copied())
+            .ok_or_else(|| "Invalid key".to_string())
+    }
+
+    fn mouse_btn(&mut self) -> Result<MouseBtn, String> {
+        Ok(match self.varint()? {
+            0 => MouseBtn::Left,
+            1 => MouseBtn::Right,
+            2 => MouseBtn::Middle,
+            3 => MouseBtn::Back,
+            4 => MouseBtn::Forward,
+            other => MouseBtn::Other(
+                u16::try_from(other - 5).map_err(|_| "Invalid mouse button".to_string())?,
+            ),
+        })
+    }
+
+    fn mouse_wheel(&mut self) -> Result<MouseWheel, String> {
+        MOUSE_WHEELS
+            .get(usize::from(self.u8()?))
+            .copied()
+            .ok_or_else(|| "Invalid mouse wheel".to_string())
+    }
+
+    fn input(bits: u8) -> Input {
+        Input::new(bits & 1 != 0, bits & 2 != 0, bits & 4 != 0)
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn gamepad_button(byte: u8) -> Result<GamepadButton, String> {
+        GAMEPAD_BUTTONS
+            .get(usize::from(byte))
+            .copied()
+            .ok_or_else(|| "Invalid gamepad button".to_string())
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn gamepad_axis(byte: u8) -> Result<GamepadAxis, String> {
+        GAMEPAD_AXES
+            .get(usize::from(byte))
+            .copied()
+            .ok_or_else(|| "Invalid gamepad axis".to_string())
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn gamepad_id(&mut self) -> Result<GamepadId, String> {
+        Ok(GamepadId(self.len()?))
+    }
+
+    fn option_string(&mut self) -> Result<Option<String>, String> {
+        Ok(if self.bool()? {
+            Some(self.string()?)
+        } else {
+            None
+        })
+    }
+
+    fn text_pos(text: &str, pos: usize) -> Result<usize, String> {
+        if text.is_char_boundary(pos) {
+            Ok(pos)
+        } else {
+            Err("Invalid text position".to_string())
+        }
+    }
+
+    fn text_state(&mut self) -> Result<TextState, String> {
+        let text = self.string()?;
+        let cursor = Self::text_pos(&text, self.len()?)?;
+        let anchor = match self.len()? {
+            0 => None,
+            anchor => Some(Self::text_pos(&text, anchor - 1)?),
+        };
+        let history = (0..self.len()?)
+            .map(|_| self.string())
+            .collect::<Result<Vec<_>, _>>()?;
+        let browsing = match self.len()? {
+            0 => None,
+            index if index <= history.len() => Some((index - 1, self.string()?)),
+            _ => return Err("Invalid history line".to_string()),
+        };
+        let modifiers = (0..self.len()?)
+            .map(|_| self.key())
+            .collect::<Result<_, _>>()?;
+        let repeat = match self.len()? {
+            0 => None,
+            key => Some((
+                KEYCODES
+                    .get(key - 1)
+                    .copied()
+                    .ok_or_else(|| "Invalid key".to_string())?,
+                self.f64()?,
+            )),
+        };
+        Ok(TextState {
+            text,
+            cursor,
+            anchor,
+            history,
+            browsing,
+            modifiers,
+            repeat,
+        })
+    }
+
+    fn state(&mut self) -> Result<InputState, String> {
+        let mut keys: [Vec<Keycodes>; 3] = Default::default();
+        for keys in &mut keys {
+            *keys = (0..self.len()?)
+                .map(|_| self.key())
+                .collect::<Result<_, _>>()?;
+        }
+        let mut mouse = Mouse::new();
+        for _ in 0..self.len()? {
+            let button = self.mouse_btn()?;
+            let input = Self::input(self.u8()?);
+            mouse.buttons.insert(button, input);
+        }
+        let pos = self.vf2d()?;
+        mouse.precise_pos = (pos.x, pos.y);
+        mouse.delta = self.f64_pair()?;
+        mouse.inside = self.bool()?;
+        mouse.wheel = self.mouse_wheel()?;
+        let lines = self.vf2d()?;
+        mouse.scroll_lines = (lines.x, lines.y);
+        mouse.scroll_pixels = self.f64_pair()?;
+        let text_input = self.bool()?;
+        let text = self.text_state()?;
+        let clipboard = self.option_string()?;
+        let accumulator = self.f64()?;
+        let pads = self.len()?;
+        #[cfg(not(feature = "gamepad"))]
+        if pads != 0 {
+            return Err(NO_GAMEPAD.to_string());
+        }
+        #[cfg(feature = "gamepad")]
+        let mut gamepads = BTreeMap::new();
+        #[cfg(feature = "gamepad")]
+        for _ in 0..pads {
+            let id = self.gamepad_id()?;
+            let mut pad = Pad {
+                name: self.string()?,
+                ..Pad::default()
+            };
+            for _ in 0..self.len()? {
+                let byte = self.u8()?;
+                pad.buttons
+                    .insert(Self::gamepad_button(byte & 0x1f)?, Self::input(byte >> 5));
+            }
+            for _ in 0..self.len()? {
+                pad.down.insert(Self::gamepad_button(self.u8()?)?);
+            }
+            for _ in 0..self.len()? {
+                let axis = Self::gamepad_axis(self.u8()?)?;
+                pad.axes.insert(axis, self.f32()?);
+            }
+            gamepads.insert(id, pad);
+        }
+        Ok(InputState {
+            keys,
+            mouse,
+            text_input,
+            text,
+            clipboard,
+            accumulator,
+            #[cfg(feature = "gamepad")]
+            gamepads,
+        })
+    }
+
+    fn event(&mut self) -> Result<InputEvent, String> {
+        Ok(match self.u8()? {
+            tag @ (0 | 1) => InputEvent::Key(self.key()?, tag == 0),
+            2 => InputEvent::Char(
+                u32::try_from(self.varint()?)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| "Invalid character".to_string())?,
+            ),
+            tag @ (3 | 4) => InputEvent::MouseButton(self.mouse_btn()?, tag == 3),
+            5 => InputEvent::MouseMove(self.vf2d()?),
+            6 => {
+                let (x, y) = self.f64_pair()?;
+                InputEvent::MouseMotion(x, y)
+            }
+            7 => InputEvent::MouseInside(self.bool()?),
+            8 => InputEvent::Wheel(self.mouse_wheel()?),
+            9 => InputEvent::ScrollLines(self.vf2d()?),
+            10 => InputEvent::ScrollPixels(self.vf2d()?),
+            #[cfg(feature = "gamepad")]
+            11 => InputEvent::Gamepad(match self.u8()? {
+                0 => GamepadEvent::Connected(self.gamepad_id()?, self.string()?),
+                1 => GamepadEvent::Disconnected(self.gamepad_id()?),
+                2 => GamepadEvent::ButtonPressed(
+                    self.gamepad_id()?,
+                    Self::gamepad_button(self.u8()?)?,
+                ),
+                3 => GamepadEvent::ButtonReleased(
+                    self.gamepad_id()?,
+                    Self::gamepad_button(self.u8()?)?,
+                ),
+                4 => GamepadEvent::AxisChanged(
+                    self.gamepad_id()?,
+                    Self::gamepad_axis(self.u8()?)?,
+                    self.f32()?,
+                ),
+                _ => return Err("Invalid gamepad event".to_string()),
+            }),
+            #[cfg(not(feature = "gamepad"))]
+            11 => return Err(NO_GAMEPAD.to_string()),
+            _ => return Err("Invalid input".to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Headless;
+
+    /// Logs everything it sees, so two runs can be compared
+    #[derive(Default)]
+    struct Logger {
+        log: Vec<String>,
+    }
+
+    impl crate::Game for Logger {
+        fn create(_engine: &mut Engine) -> Result<Self, Box<dyn std::error::Error>> {
+            Ok(Self::default())
+        }
+
+        fn update(&mut self, engine: &mut Engine) -> Result<bool, Box<dyn std::error::Error>> {
+            if engine.get_key(Keycodes::I).pressed {
+                engine.start_input();
+            }
+            let pos = engine.get_mouse_location();
+            self.log.push(format!(
+                "{} {:?} {:?} {:?} {:?}",
+                engine.elapsed,
+                engine.get_key(Keycodes::A),
+                (pos.x, pos.y),
+                engine.get_mouse_btn(MouseBtn::Left),
+                engine.get_mouse_scroll_lines(),
+            ));
+            Ok(true)
+        }
+
+        fn receive_input(&mut self, _engine: &mut Engine, input: String) {
+            self.log.push(format!("input {input}"));
+        }
+    }
+
+    fn play_inputs(headless: &mut Headless<Logger>) {
+        headless.press_key(Keycodes::A);
+        headless.step(0.1).unwrap();
+        headless.move_mouse((3, 4));
+        headless.press_mouse(MouseBtn::Left);
+        headless.scroll_lines((0.0, 1.5));
+        headless.step(0.05).unwrap();
+        headless.release_key(Keycodes::A);
+        headless.press_key(Keycodes::I);
+        headless.step(0.2).unwrap();
+        headless.type_text("héllo");
+        headless.press_key(Keycodes::Back);
+        headless.press_key(Keycodes::Return);
+        headless.release_mouse(MouseBtn::Left);
+        headless.step(0.01).unwrap();
+    }
+
+    /// A headless engine that already ran a frame and holds `A`, to check that the state at the
+    /// start of the recording is restored
+    fn started() -> Headless<Logger> {
+        let mut headless = Headless::<Logger>::new("test", (8, 8)).unwrap();
+        headless.step(0.3).unwrap();
+        headless.press_key(Keycodes::A);
+        headless
+    }
+
+    fn record() -> (Recording, Vec<String>) {
+        let mut headless = started();
+        headless.engine_mut().start_recording();
+        assert!(headless.engine().is_recording());
+        play_inputs(&mut headless);
+        let recording = headless.engine_mut().stop_recording().unwrap();
+        (recording, headless.game().log[1..].to_vec())
+    }
+
+    #[test]
+    fn replay_is_deterministic() {
+        let (recording, log) = record();
+        assert_eq!(recording.len(), 4);
+        assert!((recording.duration() - 0.36).abs() < 1e-9);
+        assert!(log.contains(&"input héll".to_string()));
+
+        for _ in 0..2 {
+            let mut headless = started();
+            headless.replay(recording.clone()).unwrap();
+            assert!(!headless.engine().is_replaying());
+            assert_eq!(headless.game().log[1..], log);
+        }
+    }
+
+    #[test]
+    fn replay_releases_inputs() {
+        let (recording, _) = record();
+        let mut headless = started();
+        headless.replay(recording).unwrap();
+        headless.step(0.1).unwrap();
+        assert!(!headless.engine().get_key(Keycodes::A).any());
+        assert!(!headless.engine().get_mouse_btn(MouseBtn::Left).any());
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let (recording, log) = record();
+        let bytes = recording.to_bytes();
+        let loaded = Recording::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded, recording);
+
+        let mut headless = started();
+        headless.replay(loaded).unwrap();
+        assert_eq!(headless.game().log[1..], log);
+
+        assert!(Recording::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Recording::from_bytes(b"nope").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use px_draw::shader::ParallelShaderTrait;
 use px_draw::traits::SmartDrawingTrait;
 
 impl px_draw::graphics::DrawSpriteTrait for crate::Engine {
@@ -81,3 +82,10 @@ impl SmartDrawingTrait for crate::Engine {
         self.screen.get_blend_factor()
     }
 }
+
+impl ParallelShaderTrait for crate::Engine {
+    fn shader_target(&mut self) -> &pixel_engine_draw::graphics::Sprite {
+        self.has_changed = true;
+        self.screen.get_ref()
+    }
+}
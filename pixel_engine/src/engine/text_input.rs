@@ -0,0 +1,758 @@
+//! Text input editing
+//!
+//! In input mode (see [`Engine::start_input`]) the keyboard edits a line of text instead of
+//! being read as key presses:
+//!
+//! - any character can be typed, this can be limited with [`Engine::set_input_max_len`] and
+//!   [`Engine::set_input_filter`]
+//! - Left/Right and Home/End move the cursor, Backspace/Delete remove a character
+//! - with Ctrl (or Alt) held, Left/Right and Backspace/Delete work on whole words
+//! - with Shift held, moving the cursor selects text, Ctrl+A selects everything
+//! - Ctrl+C, Ctrl+X and Ctrl+V copy, cut and paste with the [`Clipboard`] of the engine
+//! - Return submits the line to [`Game::receive_input`](crate::Game::receive_input) and adds it
+//!   to the history, Up/Down go through the history
+//! - Escape leaves input mode
+//!
+//! The editing keys repeat while they are held, see [`KeyRepeat`].
+//!
+//! Positions in the text (cursor and selection) are counted in characters, which is also the
+//! column of a character drawn with the font of the engine.
+//!
+//! ```
+//! use pixel_engine::inputs::Keycodes;
+//! # struct Game {
+//! #     lines: Vec<String>,
+//! # }
+//! # impl pixel_engine::Game for Game {
+//! #     fn create(engine: &mut pixel_engine::Engine) -> Result<Self, Box<dyn std::error::Error>> {
+//! #         engine.start_input();
+//! #         Ok(Game { lines: Vec::new() })
+//! #     }
+//! #     fn update(&mut self, _: &mut pixel_engine::Engine) -> Result<bool, Box<dyn std::error::Error>> {
+//! #         Ok(true)
+//! #     }
+//! #     fn receive_input(&mut self, _: &mut pixel_engine::Engine, input: String) {
+//! #         self.lines.push(input);
+//! #     }
+//! # }
+//!
+//! let mut headless = pixel_engine::Headless::<Game>::new("test", (32, 32)).unwrap();
+//! headless.type_text("héllo wörld");
+//! headless.press_key(Keycodes::LControl);
+//! headless.press_key(Keycodes::Back);
+//! headless.release_key(Keycodes::LControl);
+//! assert_eq!(headless.engine().get_input_buffer(), "héllo ");
+//! assert_eq!(headless.engine().get_input_cursor(), 6);
+//!
+//! headless.press_key(Keycodes::Return);
+//! assert_eq!(headless.game().lines, ["héllo "]);
+//! // The submitted line is in the history
+//! headless.press_key(Keycodes::Up);
+//! assert_eq!(headless.engine().get_input_buffer(), "héllo ");
+//! ```
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use super::inputs::Keycodes;
+use crate::Engine;
+
+/// Where the text input copies to and pastes from
+pub trait Clipboard {
+    /// Return the text in the clipboard, `None` if it doesn't hold any text
+    fn get_text(&mut self) -> Option<String>;
+    /// Replace the content of the clipboard
+    fn set_text(&mut self, text: String);
+}
+
+/// A [`Clipboard`] only seen by the program, used when the system clipboard isn't available
+/// and in [`Headless`](crate::Headless)
+#[derive(Debug, Clone, Default)]
+pub struct MemoryClipboard {
+    pub(crate) text: Option<String>,
+}
+
+impl Clipboard for MemoryClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.text = Some(text);
+    }
+}
+
+/// The [`Clipboard`] of the system, read with [arboard](https://docs.rs/arboard)
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+pub struct SystemClipboard(arboard::Clipboard);
+
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+impl std::fmt::Debug for SystemClipboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SystemClipboard").finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+impl SystemClipboard {
+    /// Connect to the clipboard of the system
+    ///
+    /// # Errors
+    ///
+    /// If the clipboard isn't available
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self(arboard::Clipboard::new()?))
+    }
+}
+
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+impl Clipboard for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.0.get_text().ok()
+    }
+
+    fn set_text(&mut self, text: String) {
+        // Losing a copy isn't worth stopping the game for
+        self.0.set_text(text).ok();
+    }
+}
+
+/// How the editing keys repeat while they are held in input mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyRepeat {
+    /// Seconds before the first repeat
+    pub delay: f64,
+    /// Seconds between two repeats
+    pub interval: f64,
+}
+
+impl Default for KeyRepeat {
+    fn default() -> Self {
+        Self {
+            delay: 0.5,
+            interval: 0.05,
+        }
+    }
+}
+
+/// State of the text input, it is part of the recordings
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TextState {
+    pub(crate) text: String,
+    /// Byte position of the cursor in `text`
+    pub(crate) cursor: usize,
+    /// Byte position of the other end of the selection
+    pub(crate) anchor: Option<usize>,
+    /// The submitted lines, the oldest first
+    pub(crate) history: Vec<String>,
+    /// Index of the history line being shown, with the text typed before going in the history
+    pub(crate) browsing: Option<(usize, String)>,
+    /// Modifier keys being held
+    pub(crate) modifiers: BTreeSet<Keycodes>,
+    /// Key being repeated, with the time until its next repeat
+    pub(crate) repeat: Option<(Keycodes, f64)>,
+}
+
+/// What the engine has to do after a key was pressed in input mode
+pub(crate) enum KeyAction {
+    None,
+    /// Give the line to the game
+    Submit(String),
+    /// Leave input mode
+    Cancel,
+}
+
+pub(crate) struct TextInput {
+    pub(crate) state: TextState,
+    /// Maximum number of characters
+    max_len: Option<usize>,
+    filter: Option<Box<dyn Fn(char) -> bool>>,
+    /// Maximum number of lines in the history
+    history_len: usize,
+    key_repeat: Option<KeyRepeat>,
+    clipboard: Box<dyn Clipboard>,
+    /// Used instead of `clipboard` during a replay, so the replay doesn't depend on the system
+    pub(crate) replay_clipboard: Option<MemoryClipboard>,
+}
+
+impl TextInput {
+    pub(crate) fn new(clipboard: Box<dyn Clipboard>) -> Self {
+        Self {
+            state: TextState::default(),
+            max_len: None,
+            filter: None,
+            history_len: 100,
+            key_repeat: Some(KeyRepeat::default()),
+            clipboard,
+            replay_clipboard: None,
+        }
+    }
+
+    /// Use the clipboard of the system if possible
+    pub(crate) fn system() -> Self {
+        #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+        if let Ok(clipboard) = SystemClipboard::new() {
+            return Self::new(Box::new(clipboard));
+        }
+        Self::new(Box::<MemoryClipboard>::default())
+    }
+
+    pub(crate) fn clipboard(&mut self) -> &mut dyn Clipboard {
+        match &mut self.replay_clipboard {
+            Some(clipboard) => clipboard,
+            None => self.clipboard.as_mut(),
+        }
+    }
+
+    /// Keep track of the modifiers, for every key event even outside of input mode
+    pub(crate) fn modifier(&mut self, key: Keycodes, pressed: bool) {
+        if matches!(
+            key,
+            Keycodes::LShift
+                | Keycodes::RShift
+                | Keycodes::LControl
+                | Keycodes::RControl
+                | Keycodes::LAlt
+                | Keycodes::RAlt
+                | Keycodes::LWin
+                | Keycodes::RWin
+        ) {
+            if pressed {
+                self.state.modifiers.insert(key);
+            } else {
+                self.state.modifiers.remove(&key);
+            }
+        }
+    }
+
+    fn held(&self, left: Keycodes, right: Keycodes) -> bool {
+        self.state.modifiers.contains(&left) || self.state.modifiers.contains(&right)
+    }
+
+    /// Handle a key pressed in input mode
+    pub(crate) fn key_pressed(&mut self, key: Keycodes) -> KeyAction {
+        if self.key_repeat.is_some() && matches!(self.state.repeat, Some((held, _)) if held == key)
+        {
+            // Repeated by the system, the engine does its own repeats
+            return KeyAction::None;
+        }
+        self.state.repeat = match self.key_repeat {
+            Some(repeat)
+                if matches!(
+                    key,
+                    Keycodes::Back
+                        | Keycodes::Delete
+                        | Keycodes::Left
+                        | Keycodes::Right
+                        | Keycodes::Up
+                        | Keycodes::Down
+                ) =>
+            {
+                Some((key, repeat.delay))
+            }
+            _ => None,
+        };
+        self.edit(key)
+    }
+
+    /// Handle a key released in input mode
+    pub(crate) fn key_released(&mut self, key: Keycodes) {
+        if matches!(self.state.repeat, Some((held, _)) if held == key) {
+            self.state.repeat = None;
+        }
+    }
+
+    /// Repeat the held editing key, `elapsed` seconds after the last frame
+    pub(crate) fn repeat_keys(&mut self, elapsed: f64) {
+        let (Some(repeat), Some((key, mut timer))) = (self.key_repeat, self.state.repeat) else {
+            return;
+        };
+        timer -= elapsed;
+        while timer <= 0.0 {
+            self.edit(key);
+            timer += repeat.interval.max(0.001);
+        }
+        self.state.repeat = Some((key, timer));
+    }
+
+    fn edit(&mut self, key: Keycodes) -> KeyAction {
+        let select = self.held(Keycodes::LShift, Keycodes::RShift);
+        let word = self.held(Keycodes::LControl, Keycodes::RControl)
+            || self.held(Keycodes::LAlt, Keycodes::RAlt);
+        let shortcut = self.held(Keycodes::LControl, Keycodes::RControl)
+            || self.held(Keycodes::LWin, Keycodes::RWin);
+        let cursor = self.state.cursor;
+        match key {
+            Keycodes::Escape => return KeyAction::Cancel,
+            Keycodes::Return | Keycodes::NumpadEnter => return KeyAction::Submit(self.submit()),
+            Keycodes::Back => {
+                let start = if word {
+                    prev_word(&self.state.text, cursor)
+                } else {
+                    prev_char(&self.state.text, cursor)
+                };
+                self.remove(self.selection().unwrap_or(start..cursor));
+            }
+            Keycodes::Delete => {
+                let end = if word {
+                    next_word(&self.state.text, cursor)
+                } else {
+                    next_char(&self.state.text, cursor)
+                };
+                self.remove(self.selection().unwrap_or(cursor..end));
+            }
+            Keycodes::Left => {
+                let pos = match self.selection() {
+                    Some(selection) if !select => selection.start,
+                    _ if word => prev_word(&self.state.text, cursor),
+                    _ => prev_char(&self.state.text, cursor),
+                };
+                self.move_cursor(pos, select);
+            }
+            Keycodes::Right => {
+                let pos = match self.selection() {
+                    Some(selection) if !select => selection.end,
+                    _ if word => next_word(&self.state.text, cursor),
+                    _ => next_char(&self.state.text, cursor),
+                };
+                self.move_cursor(pos, select);
+            }
+            Keycodes::Home => self.move_cursor(0, select),
+            Keycodes::End => self.move_cursor(self.state.text.len(), select),
+            Keycodes::Up => self.history_prev(),
+            Keycodes::Down => self.history_next(),
+            Keycodes::A if shortcut => {
+                self.state.anchor = Some(0);
+                self.move_cursor(self.state.text.len(), true);
+            }
+            Keycodes::C if shortcut => self.copy(),
+            Keycodes::X if shortcut => {
+                self.copy();
+                if let Some(selection) = self.selection() {
+                    self.remove(selection);
+                }
+            }
+            Keycodes::V if shortcut => {
+                if let Some(text) = self.clipboard().get_text() {
+                    self.insert_str(&text);
+                }
+            }
+            _ => {}
+        }
+        KeyAction::None
+    }
+
+    /// Return the selected bytes, `None` if nothing is selected
+    pub(crate) fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.state.anchor?;
+        let cursor = self.state.cursor;
+        Some(anchor.min(cursor)..anchor.max(cursor))
+    }
+
+    /// Return the number of characters before the byte `pos`
+    pub(crate) fn char_pos(&self, pos: usize) -> usize {
+        self.state.text[..pos].chars().count()
+    }
+
+    fn move_cursor(&mut self, pos: usize, select: bool) {
+        if select {
+            self.state.anchor.get_or_insert(self.state.cursor);
+        } else {
+            self.state.anchor = None;
+        }
+        self.state.cursor = pos;
+        if self.state.anchor == Some(pos) {
+            self.state.anchor = None;
+        }
+    }
+
+    fn remove(&mut self, range: Range<usize>) {
+        self.state.cursor = range.start;
+        self.state.anchor = None;
+        self.state.text.replace_range(range, "");
+    }
+
+    fn copy(&mut self) {
+        if let Some(selection) = self.selection() {
+            let text = self.state.text[selection].to_string();
+            self.clipboard().set_text(text);
+        }
+    }
+
+    /// Insert `text` at the cursor in place of the selection, without the control characters,
+    /// the characters rejected by the filter and the ones over the maximum length
+    pub(crate) fn insert_str(&mut self, text: &str) {
+        if let Some(selection) = self.selection() {
+            self.remove(selection);
+        }
+        let available = self.max_len.map_or(usize::MAX, |max| {
+            max.saturating_sub(self.state.text.chars().count())
+        });
+        let filter = &self.filter;
+        let text: String = text
+            .chars()
+            .filter(|chr| !chr.is_control() && filter.as_ref().is_none_or(|filter| filter(*chr)))
+            .take(available)
+            .collect();
+        self.state.text.insert_str(self.state.cursor, &text);
+        self.state.cursor += text.len();
+    }
+
+    /// Remove the text, the history is kept
+    pub(crate) fn clear(&mut self) {
+        self.state.text.clear();
+        self.state.cursor = 0;
+        self.state.anchor = None;
+        self.state.browsing = None;
+    }
+
+    /// Replace the text without filtering it and put the cursor at its end
+    fn set_text(&mut self, text: String) {
+        self.state.cursor = text.len();
+        self.state.anchor = None;
+        self.state.text = text;
+    }
+
+    /// Empty the line and add it to the history
+    fn submit(&mut self) -> String {
+        let line = std::mem::replace(&mut self.state.text, String::with_capacity(128));
+        self.clear();
+        if self.history_len > 0 && !line.is_empty() && self.state.history.last() != Some(&line) {
+            if self.state.history.len() >= self.history_len {
+                self.state.history.remove(0);
+            }
+            self.state.history.push(line.clone());
+        }
+        line
+    }
+
+    fn history_prev(&mut self) {
+        let index = match &self.state.browsing {
+            Some((index, _)) => index.checked_sub(1),
+            None => self.state.history.len().checked_sub(1),
+        };
+        let Some(index) = index else {
+            return;
+        };
+        match &mut self.state.browsing {
+            Some((browsing, _)) => *browsing = index,
+            None => self.state.browsing = Some((index, self.state.text.clone())),
+        }
+        self.set_text(self.state.history[index].clone());
+    }
+
+    fn history_next(&mut self) {
+        let Some((index, draft)) = self.state.browsing.take() else {
+            return;
+        };
+        match self.state.history.get(index + 1) {
+            Some(line) => {
+                let line = line.clone();
+                self.state.browsing = Some((index + 1, draft));
+                self.set_text(line);
+            }
+            None => self.set_text(draft),
+        }
+    }
+}
+
+fn is_word(chr: char) -> bool {
+    chr.is_alphanumeric() || chr == '_'
+}
+
+fn prev_char(text: &str, pos: usize) -> usize {
+    text[..pos]
+        .chars()
+        .next_back()
+        .map_or(pos, |chr| pos - chr.len_utf8())
+}
+
+fn next_char(text: &str, pos: usize) -> usize {
+    text[pos..]
+        .chars()
+        .next()
+        .map_or(pos, |chr| pos + chr.len_utf8())
+}
+
+/// Return the start of the word before `pos`
+fn prev_word(text: &str, pos: usize) -> usize {
+    text[..pos]
+        .char_indices()
+        .rev()
+        .skip_while(|(_, chr)| !is_word(*chr))
+        .take_while(|(_, chr)| is_word(*chr))
+        .last()
+        .map_or(0, |(start, _)| start)
+}
+
+/// Return the end of the word after `pos`
+fn next_word(text: &str, pos: usize) -> usize {
+    text[pos..]
+        .char_indices()
+        .skip_while(|(_, chr)| !is_word(*chr))
+        .find(|(_, chr)| !is_word(*chr))
+        .map_or(text.len(), |(end, _)| pos + end)
+}
+
+impl Engine {
+    /// Return the selected characters of the input buffer, `None` if nothing is selected
+    #[must_use]
+    pub fn get_input_selection(&self) -> Option<Range<usize>> {
+        let selection = self.text.selection()?;
+        Some(self.text.char_pos(selection.start)..self.text.char_pos(selection.end))
+    }
+
+    /// Limit the number of characters of the input buffer, the current text is cut if it is
+    /// longer
+    pub fn set_input_max_len(&mut self, max_len: Option<usize>) {
+        self.text.max_len = max_len;
+        if let Some((end, _)) = max_len.and_then(|max| self.text.state.text.char_indices().nth(max))
+        {
+            let state = &mut self.text.state;
+            state.text.truncate(end);
+            state.cursor = state.cursor.min(end);
+            state.anchor = state.anchor.map(|anchor| anchor.min(end));
+        }
+    }
+
+    /// Only accept the characters for which `filter` returns true in the input buffer, the
+    /// current text isn't changed
+    ///
+    /// ```
+    /// # struct Game;
+    /// # impl pixel_engine::Game for Game {
+    /// #     fn create(_: &mut pixel_engine::Engine) -> Result<Self, Box<dyn std::error::Error>> {
+    /// #         Ok(Game)
+    /// #     }
+    /// #     fn update(&mut self, _: &mut pixel_engine::Engine) -> Result<bool, Box<dyn std::error::Error>> {
+    /// #         Ok(true)
+    /// #     }
+    /// # }
+    /// # let mut headless = pixel_engine::Headless::<Game>::new("test", (32, 32)).unwrap();
+    /// # let engine = headless.engine_mut();
+    /// // Only digits
+    /// engine.set_input_filter(Some(Box::new(|chr| chr.is_ascii_digit())));
+    /// ```
+    pub fn set_input_filter(&mut self, filter: Option<Box<dyn Fn(char) -> bool>>) {
+        self.text.filter = filter;
+    }
+
+    /// Set the number of submitted lines kept in the history, `0` disables the history
+    pub fn set_input_history_len(&mut self, len: usize) {
+        self.text.history_len = len;
+        let history = &mut self.text.state.history;
+        history.drain(..history.len().saturating_sub(len));
+        self.text.state.browsing = None;
+    }
+
+    /// Return the submitted lines, the oldest first
+    #[must_use]
+    pub fn get_input_history(&self) -> &[String] {
+        &self.text.state.history
+    }
+
+    /// Remove every line of the history
+    pub fn clear_input_history(&mut self) {
+        self.text.state.history.clear();
+        self.text.state.browsing = None;
+    }
+
+    /// Set how the editing keys repeat while they are held, `None` uses the repeats of the
+    /// system if there are any
+    pub fn set_input_key_repeat(&mut self, repeat: Option<KeyRepeat>) {
+        self.text.key_repeat = repeat;
+        self.text.state.repeat = None;
+    }
+
+    /// Return the clipboard used by the text input
+    pub fn clipboard(&mut self) -> &mut dyn Clipboard {
+        self.text.clipboard()
+    }
+
+    /// Change the clipboard used by the text input, with the `clipboard` feature the engine uses
+    /// the clipboard of the system, otherwise a [`MemoryClipboard`]
+    pub fn set_clipboard(&mut self, clipboard: Box<dyn Clipboard>) {
+        self.text.clipboard = clipboard;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(text: &str) -> TextInput {
+        let mut input = TextInput::new(Box::<MemoryClipboard>::default());
+        input.insert_str(text);
+        input
+    }
+
+    fn press(input: &mut TextInput, key: Keycodes) {
+        input.key_pressed(key);
+        input.key_released(key);
+    }
+
+    fn with(input: &mut TextInput, modifier: Keycodes, key: Keycodes) {
+        input.modifier(modifier, true);
+        press(input, key);
+        input.modifier(modifier, false);
+    }
+
+    fn char_selection(input: &TextInput) -> Option<Range<usize>> {
+        input
+            .selection()
+            .map(|range| input.char_pos(range.start)..input.char_pos(range.end))
+    }
+
+    #[test]
+    fn cursor_moves_over_multi_byte_chars() {
+        let mut input = input("aé世🦀");
+        assert_eq!(input.state.cursor, 10);
+        assert_eq!(input.char_pos(input.state.cursor), 4);
+        for (bytes, chars) in [(6, 3), (3, 2), (1, 1), (0, 0), (0, 0)] {
+            press(&mut input, Keycodes::Left);
+            assert_eq!(
+                (input.state.cursor, input.char_pos(input.state.cursor)),
+                (bytes, chars)
+            );
+        }
+        for bytes in [1, 3, 6, 10, 10] {
+            press(&mut input, Keycodes::Right);
+            assert_eq!(input.state.cursor, bytes);
+        }
+    }
+
+    #[test]
+    fn delete_multi_byte_chars() {
+        let mut input = input("aé世🦀b");
+        press(&mut input, Keycodes::Left);
+        press(&mut input, Keycodes::Back);
+        assert_eq!(input.state.text, "aé世b");
+        press(&mut input, Keycodes::Home);
+        press(&mut input, Keycodes::Right);
+        press(&mut input, Keycodes::Delete);
+        assert_eq!(input.state.text, "a世b");
+        assert_eq!(input.char_pos(input.state.cursor), 1);
+        input.insert_str("ü");
+        assert_eq!(input.state.text, "aü世b");
+        assert_eq!(input.char_pos(input.state.cursor), 2);
+    }
+
+    #[test]
+    fn word_editing() {
+        let mut input = input("héllo wörld_ñ 🦀");
+        with(&mut input, Keycodes::LControl, Keycodes::Left);
+        assert_eq!(input.char_pos(input.state.cursor), 6);
+        with(&mut input, Keycodes::LControl, Keycodes::Left);
+        assert_eq!(input.char_pos(input.state.cursor), 0);
+        with(&mut input, Keycodes::LControl, Keycodes::Right);
+        assert_eq!(input.char_pos(input.state.cursor), 5);
+        with(&mut input, Keycodes::LControl, Keycodes::Delete);
+        assert_eq!(input.state.text, "héllo 🦀");
+        press(&mut input, Keycodes::End);
+        with(&mut input, Keycodes::LControl, Keycodes::Back);
+        assert_eq!(input.state.text, "");
+    }
+
+    #[test]
+    fn selection() {
+        let mut input = input("aé世🦀");
+        with(&mut input, Keycodes::LShift, Keycodes::Left);
+        with(&mut input, Keycodes::LShift, Keycodes::Left);
+        assert_eq!(char_selection(&input), Some(2..4));
+        assert_eq!(input.selection(), Some(3..10));
+        // Moving without Shift goes to the edge of the selection
+        press(&mut input, Keycodes::Right);
+        assert_eq!(input.selection(), None);
+        assert_eq!(input.char_pos(input.state.cursor), 4);
+
+        with(&mut input, Keycodes::LShift, Keycodes::Home);
+        assert_eq!(char_selection(&input), Some(0..4));
+        // Shrinking the selection back to nothing removes it
+        with(&mut input, Keycodes::LShift, Keycodes::End);
+        assert_eq!(input.selection(), None);
+
+        with(&mut input, Keycodes::LControl, Keycodes::A);
+        assert_eq!(char_selection(&input), Some(0..4));
+        input.insert_str("ö");
+        assert_eq!(input.state.text, "ö");
+        assert_eq!(input.selection(), None);
+    }
+
+    #[test]
+    fn delete_selection() {
+        let mut input = input("aé世🦀b");
+        press(&mut input, Keycodes::Left);
+        with(&mut input, Keycodes::LShift, Keycodes::Left);
+        with(&mut input, Keycodes::LShift, Keycodes::Left);
+        press(&mut input, Keycodes::Back);
+        assert_eq!(input.state.text, "aéb");
+        assert_eq!(input.char_pos(input.state.cursor), 2);
+        with(&mut input, Keycodes::LShift, Keycodes::Home);
+        press(&mut input, Keycodes::Delete);
+        assert_eq!(input.state.text, "b");
+        assert_eq!(input.state.cursor, 0);
+    }
+
+    #[test]
+    fn cut_and_paste() {
+        let mut input = input("é世🦀");
+        with(&mut input, Keycodes::LShift, Keycodes::Left);
+        with(&mut input, Keycodes::LShift, Keycodes::Left);
+        with(&mut input, Keycodes::LControl, Keycodes::X);
+        assert_eq!(input.state.text, "é");
+        assert_eq!(input.clipboard().get_text().as_deref(), Some("世🦀"));
+        press(&mut input, Keycodes::Home);
+        with(&mut input, Keycodes::LControl, Keycodes::V);
+        assert_eq!(input.state.text, "世🦀é");
+        assert_eq!(input.char_pos(input.state.cursor), 2);
+    }
+
+    #[test]
+    fn max_len_and_filter_count_chars() {
+        let mut input = input("");
+        input.max_len = Some(3);
+        input.insert_str("é世🦀ü");
+        assert_eq!(input.state.text, "é世🦀");
+        input.max_len = None;
+        input.filter = Some(Box::new(|chr| !chr.is_ascii()));
+        press(&mut input, Keycodes::Home);
+        input.insert_str("aüb\n");
+        assert_eq!(input.state.text, "üé世🦀");
+    }
+
+    #[test]
+    fn history() {
+        let mut input = input("première");
+        press(&mut input, Keycodes::Return);
+        input.insert_str("deuxième");
+        press(&mut input, Keycodes::Return);
+        input.insert_str("brouillon");
+        press(&mut input, Keycodes::Up);
+        assert_eq!(input.state.text, "deuxième");
+        press(&mut input, Keycodes::Up);
+        press(&mut input, Keycodes::Up);
+        assert_eq!(input.state.text, "première");
+        assert_eq!(input.state.cursor, "première".len());
+        press(&mut input, Keycodes::Down);
+        press(&mut input, Keycodes::Down);
+        assert_eq!(input.state.text, "brouillon");
+    }
+
+    #[test]
+    fn key_repeat() {
+        let mut input = input("aé世🦀");
+        input.key_pressed(Keycodes::Back);
+        assert_eq!(input.state.text, "aé世");
+        // Repeats from the system are ignored
+        input.key_pressed(Keycodes::Back);
+        input.repeat_keys(0.4);
+        assert_eq!(input.state.text, "aé世");
+        input.repeat_keys(0.1);
+        assert_eq!(input.state.text, "aé");
+        input.repeat_keys(0.05);
+        assert_eq!(input.state.text, "a");
+        input.key_released(Keycodes::Back);
+        input.repeat_keys(1.0);
+        assert_eq!(input.state.text, "a");
+    }
+}
@@ -0,0 +1,89 @@
+//! By default [`Game::update`](crate::Game::update) is called once per frame with whatever time
+//! passed since the last one. With a [`FixedTimestep`] the engine also calls
+//! [`Game::fixed_update`](crate::Game::fixed_update) at a constant rate, catching up when frames
+//! are slower than the step, which keeps physics deterministic and independent from the frame
+//! rate.
+
+/// Configuration of the fixed timestep mode
+///
+/// See [`Engine::set_fixed_timestep`](crate::Engine::set_fixed_timestep)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTimestep {
+    /// Duration of a step in seconds
+    pub step: f64,
+    /// Maximum number of steps run in a single frame
+    ///
+    /// When a frame takes so long that more steps are needed, the time of the missing steps is
+    /// dropped. Without this cap, a frame slower than its steps would make the next frame need
+    /// even more steps, until the game freezes.
+    pub max_steps: u32,
+}
+
+impl FixedTimestep {
+    /// Create a [`FixedTimestep`] with a step of `step` seconds
+    #[must_use]
+    pub const fn new(step: f64, max_steps: u32) -> Self {
+        Self { step, max_steps }
+    }
+
+    /// Create a [`FixedTimestep`] running `rate` steps per second, with at most 8 steps per frame
+    #[must_use]
+    pub fn from_rate(rate: f64) -> Self {
+        Self::new(rate.recip(), 8)
+    }
+}
+
+impl Default for FixedTimestep {
+    /// 60 steps per second
+    fn default() -> Self {
+        Self::from_rate(60.0)
+    }
+}
+
+/// Time not yet consumed by the steps
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Accumulator(pub(crate) f64);
+
+impl Accumulator {
+    /// Add the time of a frame and return the number of steps to run
+    pub(crate) fn advance(&mut self, timestep: FixedTimestep, elapsed: f64) -> u32 {
+        if timestep.step <= 0.0 {
+            return 0;
+        }
+        self.0 += elapsed;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let steps = (self.0 / timestep.step).floor() as u32;
+        if steps > timestep.max_steps {
+            self.0 %= timestep.step;
+            timestep.max_steps
+        } else {
+            self.0 -= f64::from(steps) * timestep.step;
+            steps
+        }
+    }
+
+    /// How far between the last step and the next one the current time is, from 0 to 1
+    pub(crate) fn alpha(self, timestep: FixedTimestep) -> f64 {
+        if timestep.step <= 0.0 {
+            return 1.0;
+        }
+        (self.0 / timestep.step).clamp(0.0, 1.0)
+    }
+}
+
+/// Wait until `deadline`, sleeping for most of the time then spinning for the last moment since
+/// sleeping isn't precise enough
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn wait_until(deadline: instant::Instant) {
+    const SPIN_TIME: std::time::Duration = std::time::Duration::from_millis(2);
+    let now = instant::Instant::now();
+    if deadline <= now {
+        return;
+    }
+    if let Some(sleep) = (deadline - now).checked_sub(SPIN_TIME) {
+        std::thread::sleep(sleep);
+    }
+    while instant::Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
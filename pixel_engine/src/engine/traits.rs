@@ -1,3 +1,4 @@
 pub use super::decals::DecalDraw;
 pub use super::decals::DecalText;
+pub use px_draw::shader::{ParallelShaderTrait, PixelShaderTrait};
 pub use px_draw::traits::*;
@@ -31,10 +31,13 @@ pub fn start<G: Game + 'static>(
     size: impl Into<px_draw::vector2::Vu2d>,
     scale: u32,
 ) {
-    let title = title.as_ref().to_string();
-    let size = size.into();
+    start_with::<G>(EngineBuilder::new(title, size, scale));
+}
+
+/// Same as [`start`] with the engine configured by an [`EngineBuilder`]
+pub fn start_with<G: Game + 'static>(builder: EngineBuilder) {
     launch(async move {
-        let engine = EngineWrapper::new(title, (size.x, size.y, scale)).await;
+        let engine = builder.build().await;
         engine.run_init::<G>();
     });
 }
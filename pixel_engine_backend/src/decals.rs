@@ -3,6 +3,9 @@ use float_next_after::NextAfter;
 use wgpu::util::DeviceExt;
 pub type DecalTextureID = usize;
 
+/// Id of the decals without a texture, `0` is the main screen texture
+const DETACHED_ID: DecalTextureID = DecalTextureID::MAX;
+
 mod gpu_vector;
 
 #[derive(Debug)]
@@ -79,20 +82,7 @@ impl DecalContextManager {
                     let mut out = std::collections::HashMap::with_capacity(64);
 
                     let tex = crate::texture::Texture::from_bytes(device, queue, spr);
-                    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                        layout: bind_group_layout,
-                        entries: &[
-                            wgpu::BindGroupEntry {
-                                binding: 0,
-                                resource: wgpu::BindingResource::TextureView(&tex.view),
-                            },
-                            wgpu::BindGroupEntry {
-                                binding: 1,
-                                resource: wgpu::BindingResource::Sampler(&tex.sampler),
-                            },
-                        ],
-                        label: Some("decal_bindgroup"),
-                    });
+                    let bind_group = texture_bind_group(device, bind_group_layout, &tex);
 
                     out.insert(0usize, (tex, bind_group));
                     out
@@ -128,6 +118,40 @@ impl DecalContextManager {
     pub fn update_main_texture(&mut self, queue: &wgpu::Queue, data: &[u8]) {
         (self.decal_textures[&0].0).update(queue, data);
     }
+
+    /// Replace the main texture by a new one, used when the screen changes size
+    pub fn recreate_main_texture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        spr: (&[u8], (u32, u32)),
+    ) {
+        let tex = crate::texture::Texture::from_bytes(device, queue, spr);
+        let bind_group = texture_bind_group(device, bind_group_layout, &tex);
+        self.decal_textures.insert(0, (tex, bind_group));
+    }
+}
+
+fn texture_bind_group(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    tex: &crate::texture::Texture,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&tex.view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&tex.sampler),
+            },
+        ],
+        label: Some("decal_bindgroup"),
+    })
 }
 
 #[derive(Debug, Clone)]
@@ -143,20 +167,7 @@ impl Decal {
     pub fn create(ctx: &mut crate::Context, sprite: (&[u8], (u32, u32))) -> Self {
         let id = ctx.dcm.id_generator.get();
         let tex = crate::texture::Texture::from_bytes(&ctx.device, &ctx.queue, sprite);
-        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &ctx.bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&tex.view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&tex.sampler),
-                },
-            ],
-            label: Some("decal_bindgroup"),
-        });
+        let bind_group = texture_bind_group(&ctx.device, &ctx.bind_group_layout, &tex);
         ctx.dcm.decal_textures.insert(id, (tex, bind_group));
 
         Self {
@@ -166,8 +177,27 @@ impl Decal {
         }
     }
 
+    /// A decal without a GPU texture, for engines running without a [`Context`](crate::Context)
+    #[must_use]
+    pub fn detached(size: (u32, u32)) -> Self {
+        Self {
+            id: DETACHED_ID,
+            size,
+            uv_scale: (1.0 / size.0.max(1) as f32, 1.0 / size.1.max(1) as f32),
+        }
+    }
+
+    /// Destroy the texture of the decal, this does nothing for a [detached](Decal::detached) decal
     pub fn destroy(&self, ctx: &mut crate::Context) {
-        ctx.dcm.decal_textures.remove(&self.id);
+        if !self.is_detached() {
+            ctx.dcm.decal_textures.remove(&self.id);
+        }
+    }
+
+    /// Whether this decal was created by [`Decal::detached`]
+    #[must_use]
+    pub fn is_detached(&self) -> bool {
+        self.id == DETACHED_ID
     }
 
     #[must_use]
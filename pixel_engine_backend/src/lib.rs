@@ -87,12 +87,30 @@ pub(crate) const INDICES: &[u16] = &[
     1, 2, 3,
 ];
 
+/// How rendered frames are shown on the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PresentMode {
+    /// Wait for the screen's vertical blank, the frame rate is capped to the refresh rate and
+    /// there is no tearing
+    Vsync,
+    /// Show frames as soon as they are rendered, may tear
+    #[default]
+    NoVsync,
+    /// Show the latest rendered frame at the vertical blank without waiting for it, there is no
+    /// tearing. When it isn't supported [`PresentMode::Vsync`] is used instead
+    Mailbox,
+}
+
 #[allow(dead_code)]
 pub struct Context {
     surface: wgpu::Surface,
     device: wgpu::Device,
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
+    present_mode: PresentMode,
+    supported_present_modes: Vec<wgpu::PresentMode>,
+    clear_color: wgpu::Color,
+    viewport: Option<(f32, f32, f32, f32)>,
 
     render_pipeline: wgpu::RenderPipeline,
     vertex_buffer: wgpu::Buffer,
@@ -104,7 +122,7 @@ pub struct Context {
 }
 
 impl Context {
-    pub async fn new(window: &Window, px_size: (u32, u32, u32)) -> Self {
+    pub async fn new(window: &Window, px_size: (u32, u32, u32), present_mode: PresentMode) -> Self {
         let size = window.inner_size();
         let instance = wgpu::Instance::new(wgpu::Backends::all());
 
@@ -135,7 +153,7 @@ impl Context {
             .expect("Error when getting device and queue");
 
         device.on_uncaptured_error(|error| panic!("[WGPU Error] {}", error));
-        let config = wgpu::SurfaceConfiguration {
+        let mut config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             width: size.width,
             height: size.height,
@@ -146,6 +164,9 @@ impl Context {
             present_mode: wgpu::PresentMode::AutoNoVsync,
             alpha_mode: wgpu::CompositeAlphaMode::Auto,
         };
+        let supported_present_modes = surface.get_supported_present_modes(&adapter);
+        let present_mode = Self::check_present_mode(&supported_present_modes, present_mode);
+        config.present_mode = Self::wgpu_present_mode(present_mode);
 
         surface.configure(&device, &config);
 
@@ -277,6 +298,10 @@ impl Context {
             render_pipeline,
             vertex_buffer,
             config,
+            present_mode,
+            supported_present_modes,
+            clear_color: wgpu::Color::BLACK,
+            viewport: None,
             index_buffer,
             num_indices,
             bind_group_layout: texture_bind_group_layout,
@@ -284,6 +309,63 @@ impl Context {
         }
     }
 
+    fn check_present_mode(supported: &[wgpu::PresentMode], mode: PresentMode) -> PresentMode {
+        match mode {
+            PresentMode::Mailbox if !supported.contains(&wgpu::PresentMode::Mailbox) => {
+                PresentMode::Vsync
+            }
+            mode => mode,
+        }
+    }
+
+    fn wgpu_present_mode(mode: PresentMode) -> wgpu::PresentMode {
+        match mode {
+            PresentMode::Vsync => wgpu::PresentMode::AutoVsync,
+            PresentMode::NoVsync => wgpu::PresentMode::AutoNoVsync,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+        }
+    }
+
+    /// Change the present mode, return the mode that is really used
+    pub fn set_present_mode(&mut self, mode: PresentMode) -> PresentMode {
+        self.present_mode = Self::check_present_mode(&self.supported_present_modes, mode);
+        self.config.present_mode = Self::wgpu_present_mode(self.present_mode);
+        self.surface.configure(&self.device, &self.config);
+        self.present_mode
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    /// Set the color drawn behind the screen, in linear RGBA
+    pub fn set_clear_color(&mut self, color: [f64; 4]) {
+        let [r, g, b, a] = color;
+        self.clear_color = wgpu::Color { r, g, b, a };
+    }
+
+    /// Resize the surface to the new size of the window
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.config.width = width;
+        self.config.height = height;
+        self.surface.configure(&self.device, &self.config);
+    }
+
+    /// Change the size of the screen texture, `data` is its new content
+    pub fn resize_screen(&mut self, data: &[u8], size: (u32, u32)) {
+        self.dcm
+            .recreate_main_texture(&self.device, &self.queue, &self.bind_group_layout, (data, size));
+    }
+
+    /// Draw the screen and the decals in the `(x, y, width, height)` part of the window, `None`
+    /// uses the whole window
+    pub fn set_viewport(&mut self, viewport: Option<(f32, f32, f32, f32)>) {
+        self.viewport = viewport;
+    }
+
     pub fn render(&mut self, data: &[u8]) {
         self.dcm.update_main_texture(&self.queue, data);
         self.render_no_update();
@@ -306,7 +388,7 @@ impl Context {
                         view: &view,
                         resolve_target: None,
                         ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            load: wgpu::LoadOp::Clear(self.clear_color),
 
                             store: true,
                         },
@@ -318,6 +400,15 @@ impl Context {
                 use decals::DrawDecals;
 
                 render_pass.set_pipeline(&self.render_pipeline);
+                if let Some((x, y, w, h)) = self.viewport {
+                    // The viewport needs to be inside of the surface
+                    let (sw, sh) = (self.config.width as f32, self.config.height as f32);
+                    let (x, y) = (x.clamp(0.0, sw), y.clamp(0.0, sh));
+                    let (w, h) = (w.min(sw - x), h.min(sh - y));
+                    if w > 0.0 && h > 0.0 {
+                        render_pass.set_viewport(x, y, w, h, 0.0, 1.0);
+                    }
+                }
 
                 render_pass.draw_decals(&mut self.dcm, &mut self.device, &mut self.queue);
             }
@@ -0,0 +1,414 @@
+#![allow(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_truncation,
+    clippy::many_single_char_names
+)]
+//! Color spaces, arithmetic and colormaps for [`Color`]
+//!
+//! All the float representations use channels in `[0.0; 1.0]` and hues in degrees.
+
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+
+use crate::graphics::Color;
+
+#[inline]
+fn to_u8(v: f32) -> u8 {
+    (v * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// Convert a sRGB encoded channel into linear light
+#[must_use]
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a linear light channel into sRGB encoding
+#[must_use]
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Error returned when parsing a [`Color`] from an hex string
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ParseColorError;
+
+impl std::fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid hex color, expected `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`"
+        )
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl Color {
+    /// Return the same [`Color`] with the given alpha
+    #[must_use]
+    pub const fn with_alpha(self, a: u8) -> Color {
+        Color { a, ..self }
+    }
+
+    /// Create a [`Color`] from hue (degrees), saturation and value
+    #[must_use]
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Color {
+        let s = s.clamp(0.0, 1.0);
+        let v = v.clamp(0.0, 1.0);
+        let c = v * s;
+        Self::from_hue_chroma(h, c, v - c)
+    }
+
+    /// Return the hue (degrees), saturation and value of the [`Color`]
+    #[must_use]
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let [r, g, b]: [f32; 3] = self.into();
+        let max = r.max(g).max(b);
+        let d = max - r.min(g).min(b);
+        let s = if max > 0.0 { d / max } else { 0.0 };
+        (Self::hue(r, g, b, max, d), s, max)
+    }
+
+    /// Create a [`Color`] from hue (degrees), saturation and lightness
+    #[must_use]
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Color {
+        let s = s.clamp(0.0, 1.0);
+        let l = l.clamp(0.0, 1.0);
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Self::from_hue_chroma(h, c, l - c * 0.5)
+    }
+
+    /// Return the hue (degrees), saturation and lightness of the [`Color`]
+    #[must_use]
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let [r, g, b]: [f32; 3] = self.into();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let d = max - min;
+        let l = (max + min) * 0.5;
+        let s = if d > 0.0 {
+            d / (1.0 - (2.0 * l - 1.0).abs())
+        } else {
+            0.0
+        };
+        (Self::hue(r, g, b, max, d), s, l)
+    }
+
+    /// Shared hue computation of HSV and HSL
+    fn hue(r: f32, g: f32, b: f32, max: f32, d: f32) -> f32 {
+        if d <= 0.0 {
+            0.0
+        } else if (max - r).abs() < f32::EPSILON {
+            60.0 * ((g - b) / d).rem_euclid(6.0)
+        } else if (max - g).abs() < f32::EPSILON {
+            60.0 * ((b - r) / d + 2.0)
+        } else {
+            60.0 * ((r - g) / d + 4.0)
+        }
+    }
+
+    /// Shared color construction of HSV and HSL from the hue, chroma and the lightness offset
+    fn from_hue_chroma(h: f32, c: f32, m: f32) -> Color {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        Color::new(to_u8(r + m), to_u8(g + m), to_u8(b + m))
+    }
+
+    /// Create a [`Color`] from the `OKLab` perceptual color space
+    ///
+    /// `l` is in `[0.0; 1.0]`, `a` and `b` are roughly in `[-0.4; 0.4]`
+    #[must_use]
+    pub fn from_oklab(l: f32, a: f32, b: f32) -> Color {
+        let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+        let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+        let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+        let (l3, m3, s3) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+        Self::from_linear([
+            4.076_741_7 * l3 - 3.307_711_6 * m3 + 0.230_969_94 * s3,
+            -1.268_438 * l3 + 2.609_757_4 * m3 - 0.341_319_38 * s3,
+            -0.004_196_086_3 * l3 - 0.703_418_6 * m3 + 1.707_614_7 * s3,
+            1.0,
+        ])
+    }
+
+    /// Return the `(l, a, b)` coordinates of the [`Color`] in the `OKLab` perceptual color space
+    #[must_use]
+    pub fn to_oklab(self) -> (f32, f32, f32) {
+        let [r, g, b, _] = self.to_linear();
+        let l = (0.412_221_47 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        (
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        )
+    }
+
+    /// Return the channels of the [`Color`] in linear light, the alpha is left as is
+    #[must_use]
+    pub fn to_linear(self) -> [f32; 4] {
+        let [r, g, b, a]: [f32; 4] = self.into();
+        [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a]
+    }
+
+    /// Create a [`Color`] from channels in linear light, the alpha is left as is
+    #[must_use]
+    pub fn from_linear(col: [f32; 4]) -> Color {
+        Color::new_with_alpha(
+            to_u8(linear_to_srgb(col[0].clamp(0.0, 1.0))),
+            to_u8(linear_to_srgb(col[1].clamp(0.0, 1.0))),
+            to_u8(linear_to_srgb(col[2].clamp(0.0, 1.0))),
+            to_u8(col[3]),
+        )
+    }
+
+    /// Parse a [`Color`] from an hex string (`#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`), the
+    /// `#` is optional
+    ///
+    /// # Errors
+    ///
+    /// If the string isn't in one of those formats
+    pub fn from_hex(hex: &str) -> Result<Color, ParseColorError> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseColorError);
+        }
+        let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).map_err(|_| ParseColorError);
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| ParseColorError);
+        match hex.len() {
+            3 | 4 => {
+                let mut out = [255u8; 4];
+                for (i, c) in out.iter_mut().take(hex.len()).enumerate() {
+                    *c = digit(i)? * 0x11;
+                }
+                Ok(out.into())
+            }
+            6 | 8 => {
+                let mut out = [255u8; 4];
+                for (i, c) in out.iter_mut().take(hex.len() / 2).enumerate() {
+                    *c = byte(i * 2)?;
+                }
+                Ok(out.into())
+            }
+            _ => Err(ParseColorError),
+        }
+    }
+
+    /// Format the [`Color`] as `#rrggbb`, or `#rrggbbaa` if it isn't opaque
+    #[must_use]
+    pub fn to_hex(self) -> String {
+        if self.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    /// Linearly interpolate between two colors (alpha included), `t = 0.0` returning `self`
+    /// and `t = 1.0` returning `other`
+    #[must_use]
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8;
+        Color::new_with_alpha(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+            mix(self.a, other.a),
+        )
+    }
+
+    /// Increase the lightness (in HSL) of the color by `amount` (`[0.0; 1.0]`)
+    #[must_use]
+    pub fn lighten(self, amount: f32) -> Color {
+        let (h, s, l) = self.to_hsl();
+        Color::from_hsl(h, s, l + amount).with_alpha(self.a)
+    }
+
+    /// Decrease the lightness (in HSL) of the color by `amount` (`[0.0; 1.0]`)
+    #[must_use]
+    pub fn darken(self, amount: f32) -> Color {
+        self.lighten(-amount)
+    }
+
+    /// Return the luma of the color (`[0; 255]`), the perceived brightness
+    #[must_use]
+    pub fn luma(self) -> u8 {
+        (0.299 * f32::from(self.r) + 0.587 * f32::from(self.g) + 0.114 * f32::from(self.b)).round()
+            as u8
+    }
+}
+
+impl std::str::FromStr for Color {
+    type Err = ParseColorError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::from_hex(s)
+    }
+}
+
+/// Saturating addition of the color channels, the alpha of the left hand side is kept
+impl Add for Color {
+    type Output = Color;
+    fn add(self, rhs: Color) -> Color {
+        Color::new_with_alpha(
+            self.r.saturating_add(rhs.r),
+            self.g.saturating_add(rhs.g),
+            self.b.saturating_add(rhs.b),
+            self.a,
+        )
+    }
+}
+
+/// Saturating substraction of the color channels, the alpha of the left hand side is kept
+impl Sub for Color {
+    type Output = Color;
+    fn sub(self, rhs: Color) -> Color {
+        Color::new_with_alpha(
+            self.r.saturating_sub(rhs.r),
+            self.g.saturating_sub(rhs.g),
+            self.b.saturating_sub(rhs.b),
+            self.a,
+        )
+    }
+}
+
+/// Modulate two colors, every channel (alpha included) is multiplied as if it was in
+/// `[0.0; 1.0]`, this is how decals are tinted
+impl Mul for Color {
+    type Output = Color;
+    fn mul(self, rhs: Color) -> Color {
+        let mul = |a: u8, b: u8| ((u16::from(a) * u16::from(b) + 127) / 255) as u8;
+        Color::new_with_alpha(
+            mul(self.r, rhs.r),
+            mul(self.g, rhs.g),
+            mul(self.b, rhs.b),
+            mul(self.a, rhs.a),
+        )
+    }
+}
+
+/// Scale the color channels by a factor, saturating at 255, the alpha is kept
+impl Mul<f32> for Color {
+    type Output = Color;
+    fn mul(self, rhs: f32) -> Color {
+        let mul = |a: u8| (f32::from(a) * rhs).round().clamp(0.0, 255.0) as u8;
+        Color::new_with_alpha(mul(self.r), mul(self.g), mul(self.b), self.a)
+    }
+}
+
+impl AddAssign for Color {
+    fn add_assign(&mut self, rhs: Color) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Color {
+    fn sub_assign(&mut self, rhs: Color) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Color {
+    fn mul_assign(&mut self, rhs: Color) {
+        *self = *self * rhs;
+    }
+}
+
+impl MulAssign<f32> for Color {
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
+
+/// Maps scalar values to colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colormap {
+    /// From black to white
+    Grayscale,
+    /// Perceptually uniform, from dark purple to yellow
+    Viridis,
+    /// Perceptually uniform, from black to light yellow through purple and orange
+    Magma,
+    /// Rainbow-like, from dark blue to dark red
+    Turbo,
+}
+
+/// Evaluate a polynomial for each channel, `c` being the coefficients in increasing degree
+fn polynomial(c: &[[f32; 3]], t: f32) -> Color {
+    let mut out = [0f32; 3];
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = c.iter().rev().fold(0.0, |acc, k| acc * t + k[i]);
+    }
+    out.into()
+}
+
+impl Colormap {
+    /// Return the [`Color`] at `t`, `t` is clamped to `[0.0; 1.0]`
+    #[must_use]
+    pub fn sample(self, t: f32) -> Color {
+        // Polynomial fits by Matt Zucker (viridis, magma) and Google (turbo)
+        #[allow(clippy::unreadable_literal, clippy::excessive_precision)]
+        const VIRIDIS: [[f32; 3]; 7] = [
+            [0.277727327, 0.005407344, 0.334099805],
+            [0.105093043, 1.404613529, 1.384590162],
+            [-0.330861828, 0.214847559, 0.095095163],
+            [-4.634230498, -5.799100973, -19.332440956],
+            [6.228269936, 14.179933366, 56.690552600],
+            [4.776384997, -13.745145377, -65.353032633],
+            [-5.435455855, 4.645852612, 26.312435249],
+        ];
+        #[allow(clippy::unreadable_literal, clippy::excessive_precision)]
+        const MAGMA: [[f32; 3]; 7] = [
+            [-0.002136485, -0.000749655, -0.005386127],
+            [0.251660540, 0.677523243, 2.494026599],
+            [8.353717279, -3.577719514, 0.314467903],
+            [-27.668733085, 14.264730780, -13.649213188],
+            [52.176139812, -27.943606071, 12.944169442],
+            [-50.768525364, 29.046582821, 4.234152993],
+            [18.655705065, -11.489773519, -5.601961508],
+        ];
+        #[allow(clippy::unreadable_literal, clippy::excessive_precision)]
+        const TURBO: [[f32; 3]; 6] = [
+            [0.13572138, 0.09140261, 0.10667330],
+            [4.61539260, 2.19418839, 12.64194608],
+            [-42.66032258, 4.84296658, -60.58204836],
+            [132.13108234, -14.18503333, 110.36276771],
+            [-152.94239396, 4.27729857, -89.90310912],
+            [59.28637943, 2.82956604, 27.34824973],
+        ];
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Colormap::Grayscale => [t, t, t].into(),
+            Colormap::Viridis => polynomial(&VIRIDIS, t),
+            Colormap::Magma => polynomial(&MAGMA, t),
+            Colormap::Turbo => polynomial(&TURBO, t),
+        }
+    }
+
+    /// Map `value` from `[min; max]` onto the colormap
+    #[must_use]
+    pub fn map(self, value: f32, min: f32, max: f32) -> Color {
+        if max <= min {
+            return self.sample(0.0);
+        }
+        self.sample((value - min) / (max - min))
+    }
+}
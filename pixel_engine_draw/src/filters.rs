@@ -0,0 +1,395 @@
+#![allow(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss
+)]
+//! Image processing on [`Sprite`]s
+//!
+//! Every filter works in place, clone the sprite first if you need to keep the original.
+//! The pixels outside of the sprite are treated as copies of the closest edge pixel.
+
+use crate::graphics::{Color, Sprite};
+
+/// Largest radius of a [`Kernel::gaussian`]
+const MAX_KERNEL_RADIUS: u32 = 256;
+
+/// A convolution kernel
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kernel {
+    width: usize,
+    height: usize,
+    weights: Vec<f32>,
+    /// Value added to every channel after the convolution (in `[0; 255]`)
+    pub bias: f32,
+}
+
+impl Kernel {
+    /// Create a kernel from its weights, given row by row
+    ///
+    /// # Panics
+    ///
+    /// If the width or the height is even or if `weights.len() != width * height`
+    #[must_use]
+    pub fn new(width: usize, height: usize, weights: &[f32]) -> Self {
+        assert!(
+            width % 2 == 1 && height % 2 == 1,
+            "A kernel must have an odd size"
+        );
+        assert_eq!(weights.len(), width * height, "Wrong kernel len");
+        Self {
+            width,
+            height,
+            weights: weights.to_vec(),
+            bias: 0.0,
+        }
+    }
+
+    /// Return the same kernel with every weight divided by their sum, if the sum isn't 0
+    #[must_use]
+    pub fn normalized(mut self) -> Self {
+        let sum: f32 = self.weights.iter().sum();
+        if sum != 0.0 {
+            self.weights.iter_mut().for_each(|w| *w /= sum);
+        }
+        self
+    }
+
+    /// Return the same kernel with the given bias
+    #[must_use]
+    pub fn with_bias(mut self, bias: f32) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// A `(2 * radius + 1)` square kernel where all weights are equal
+    #[must_use]
+    pub fn box_blur(radius: usize) -> Self {
+        let size = 2 * radius + 1;
+        Self::new(size, size, &vec![1.0; size * size]).normalized()
+    }
+
+    /// A square gaussian kernel, the radius is `ceil(3 * sigma)` up to 256
+    ///
+    /// A `NaN` or non positive sigma gives the identity, an infinite one a box blur
+    #[must_use]
+    pub fn gaussian(sigma: f32) -> Self {
+        let line = gaussian_weights(sigma, MAX_KERNEL_RADIUS);
+        let size = line.len();
+        let weights: Vec<f32> = line
+            .iter()
+            .flat_map(|y| line.iter().map(move |x| x * y))
+            .collect();
+        Self::new(size, size, &weights).normalized()
+    }
+
+    /// A 3x3 sharpening kernel
+    #[must_use]
+    pub fn sharpen() -> Self {
+        #[rustfmt::skip]
+        let weights = [
+             0.0, -1.0,  0.0,
+            -1.0,  5.0, -1.0,
+             0.0, -1.0,  0.0,
+        ];
+        Self::new(3, 3, &weights)
+    }
+
+    /// A 3x3 laplacian kernel, highlighting the edges in every direction
+    #[must_use]
+    pub fn laplacian() -> Self {
+        #[rustfmt::skip]
+        let weights = [
+            -1.0, -1.0, -1.0,
+            -1.0,  8.0, -1.0,
+            -1.0, -1.0, -1.0,
+        ];
+        Self::new(3, 3, &weights)
+    }
+
+    /// A 3x3 emboss kernel
+    #[must_use]
+    pub fn emboss() -> Self {
+        #[rustfmt::skip]
+        let weights = [
+            -2.0, -1.0, 0.0,
+            -1.0,  1.0, 1.0,
+             0.0,  1.0, 2.0,
+        ];
+        Self::new(3, 3, &weights)
+    }
+}
+
+/// Return the normalized 1D gaussian weights for the given sigma, with at most `max_radius`
+/// weights on each side
+fn gaussian_weights(sigma: f32, max_radius: u32) -> Vec<f32> {
+    // `max` also turns NaN into EPSILON, and an infinite sigma makes every weight 1
+    let sigma = sigma.max(f32::EPSILON);
+    let radius = i64::from(((3.0 * sigma).ceil() as u32).min(max_radius));
+    let weights: Vec<f32> = (-radius..=radius)
+        .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = weights.iter().sum();
+    weights.into_iter().map(|w| w / sum).collect()
+}
+
+#[inline]
+fn to_u8(v: f32) -> u8 {
+    v.round().clamp(0.0, 255.0) as u8
+}
+
+#[inline]
+fn luma(px: &[u8]) -> f32 {
+    0.299 * f32::from(px[0]) + 0.587 * f32::from(px[1]) + 0.114 * f32::from(px[2])
+}
+
+impl Sprite {
+    /// Run `f` on every pixel's rgba bytes
+    fn map_raw(&mut self, mut f: impl FnMut(&mut [u8])) {
+        let (raw, lock) = self.get_read_lock();
+        raw.chunks_exact_mut(4).for_each(&mut f);
+        drop(lock);
+    }
+
+    /// Run a 1D convolution on all 4 channels, either horizontally or vertically
+    fn convolve_1d(&mut self, weights: &[f32], horizontal: bool) {
+        let (w, h) = (self.width() as usize, self.height() as usize);
+        if w == 0 || h == 0 {
+            return;
+        }
+        let radius = (weights.len() / 2) as isize;
+        let (raw, lock) = self.get_read_lock();
+        let src = raw.to_vec();
+        for y in 0..h {
+            for x in 0..w {
+                let mut acc = [0f32; 4];
+                for (k, weight) in weights.iter().enumerate() {
+                    let o = k as isize - radius;
+                    let (sx, sy) = if horizontal {
+                        ((x as isize + o).clamp(0, w as isize - 1) as usize, y)
+                    } else {
+                        (x, (y as isize + o).clamp(0, h as isize - 1) as usize)
+                    };
+                    let i = (sy * w + sx) * 4;
+                    for c in 0..4 {
+                        acc[c] += f32::from(src[i + c]) * weight;
+                    }
+                }
+                let i = (y * w + x) * 4;
+                for c in 0..4 {
+                    raw[i + c] = to_u8(acc[c]);
+                }
+            }
+        }
+        drop(lock);
+    }
+
+    /// Apply the given convolution [`Kernel`] on the color channels, the alpha is kept as is
+    pub fn convolve(&mut self, kernel: &Kernel) {
+        let (w, h) = (self.width() as isize, self.height() as isize);
+        if w == 0 || h == 0 {
+            return;
+        }
+        let (rx, ry) = ((kernel.width / 2) as isize, (kernel.height / 2) as isize);
+        let (raw, lock) = self.get_read_lock();
+        let src = raw.to_vec();
+        for y in 0..h {
+            for x in 0..w {
+                let mut acc = [kernel.bias; 3];
+                for ky in -ry..=ry {
+                    let sy = (y + ky).clamp(0, h - 1);
+                    for kx in -rx..=rx {
+                        let sx = (x + kx).clamp(0, w - 1);
+                        let weight =
+                            kernel.weights[((ky + ry) * kernel.width as isize + kx + rx) as usize];
+                        let i = ((sy * w + sx) * 4) as usize;
+                        for c in 0..3 {
+                            acc[c] += f32::from(src[i + c]) * weight;
+                        }
+                    }
+                }
+                let i = ((y * w + x) * 4) as usize;
+                for c in 0..3 {
+                    raw[i + c] = to_u8(acc[c]);
+                }
+            }
+        }
+        drop(lock);
+    }
+
+    /// Blur the sprite (alpha included) by averaging each pixel with its neighbours in a
+    /// `(2 * radius + 1)` square
+    ///
+    /// The radius is clamped to the largest dimension of the sprite
+    pub fn box_blur(&mut self, radius: u32) {
+        let radius = radius.min(self.width().max(self.height()));
+        let size = 2 * radius as usize + 1;
+        let weights = vec![1.0 / size as f32; size];
+        self.convolve_1d(&weights, true);
+        self.convolve_1d(&weights, false);
+    }
+
+    /// Blur the sprite (alpha included) with a gaussian of the given standard deviation
+    ///
+    /// The radius (`ceil(3 * sigma)`) is clamped to the largest dimension of the sprite.
+    /// A `NaN` or non positive sigma leaves the sprite as is, an infinite one is a box blur of
+    /// that radius
+    pub fn gaussian_blur(&mut self, sigma: f32) {
+        let weights = gaussian_weights(sigma, self.width().max(self.height()));
+        self.convolve_1d(&weights, true);
+        self.convolve_1d(&weights, false);
+    }
+
+    /// Sharpen the sprite, see [`Kernel::sharpen`]
+    pub fn sharpen(&mut self) {
+        self.convolve(&Kernel::sharpen());
+    }
+
+    /// Replace the sprite by the magnitude of its gradient using the Sobel operator
+    /// The result is in grayscale, bright pixels being edges. The alpha is kept as is.
+    pub fn edge_detect(&mut self) {
+        let (w, h) = (self.width() as isize, self.height() as isize);
+        if w == 0 || h == 0 {
+            return;
+        }
+        let (raw, lock) = self.get_read_lock();
+        let lum: Vec<f32> = raw.chunks_exact(4).map(luma).collect();
+        let at = |x: isize, y: isize| lum[(y.clamp(0, h - 1) * w + x.clamp(0, w - 1)) as usize];
+        for y in 0..h {
+            for x in 0..w {
+                let gx = at(x + 1, y - 1) + 2.0 * at(x + 1, y) + at(x + 1, y + 1)
+                    - at(x - 1, y - 1)
+                    - 2.0 * at(x - 1, y)
+                    - at(x - 1, y + 1);
+                let gy = at(x - 1, y + 1) + 2.0 * at(x, y + 1) + at(x + 1, y + 1)
+                    - at(x - 1, y - 1)
+                    - 2.0 * at(x, y - 1)
+                    - at(x + 1, y - 1);
+                let v = to_u8(gx.hypot(gy));
+                let i = ((y * w + x) * 4) as usize;
+                raw[i..i + 3].fill(v);
+            }
+        }
+        drop(lock);
+    }
+
+    /// Convert the sprite to grayscale using the luma of each pixel
+    pub fn grayscale(&mut self) {
+        self.map_raw(|px| {
+            let v = to_u8(luma(px));
+            px[..3].fill(v);
+        });
+    }
+
+    /// Give the sprite a brownish old photo look
+    pub fn sepia(&mut self) {
+        self.map_raw(|px| {
+            let (r, g, b) = (f32::from(px[0]), f32::from(px[1]), f32::from(px[2]));
+            px[0] = to_u8(0.393 * r + 0.769 * g + 0.189 * b);
+            px[1] = to_u8(0.349 * r + 0.686 * g + 0.168 * b);
+            px[2] = to_u8(0.272 * r + 0.534 * g + 0.131 * b);
+        });
+    }
+
+    /// Invert the color channels, the alpha is kept as is
+    pub fn invert(&mut self) {
+        self.map_raw(|px| {
+            px[0] = 255 - px[0];
+            px[1] = 255 - px[1];
+            px[2] = 255 - px[2];
+        });
+    }
+
+    /// Add `amount` to every color channel, `amount` is in `[-1.0; 1.0]`, `1.0` making the
+    /// sprite completely white
+    pub fn brightness(&mut self, amount: f32) {
+        let offset = amount * 255.0;
+        self.map_raw(|px| {
+            for c in &mut px[..3] {
+                *c = to_u8(f32::from(*c) + offset);
+            }
+        });
+    }
+
+    /// Scale the distance of every color channel to the middle gray by `factor`
+    /// `factor > 1.0` increases the contrast, `factor < 1.0` decreases it
+    pub fn contrast(&mut self, factor: f32) {
+        self.map_raw(|px| {
+            for c in &mut px[..3] {
+                *c = to_u8((f32::from(*c) - 127.5) * factor + 127.5);
+            }
+        });
+    }
+
+    /// Rotate the hue of every pixel by `degrees`
+    pub fn hue_shift(&mut self, degrees: f32) {
+        self.map_raw(|px| {
+            let (h, s, l) = Color::from([px[0], px[1], px[2]]).to_hsl();
+            px[..3].copy_from_slice(&<[u8; 3]>::from(Color::from_hsl(h + degrees, s, l)));
+        });
+    }
+
+    /// Multiply the saturation of every pixel by `factor`, `0.0` giving a grayscale sprite
+    pub fn saturate(&mut self, factor: f32) {
+        self.map_raw(|px| {
+            let (h, s, l) = Color::from([px[0], px[1], px[2]]).to_hsl();
+            px[..3].copy_from_slice(&<[u8; 3]>::from(Color::from_hsl(h, s * factor, l)));
+        });
+    }
+
+    /// Turn every pixel with a luma greater or equal to `level` into `high`, and the others into
+    /// `low`
+    pub fn threshold(&mut self, level: u8, low: Color, high: Color) {
+        let level = f32::from(level);
+        let low: [u8; 4] = low.into();
+        let high: [u8; 4] = high.into();
+        self.map_raw(|px| {
+            px.copy_from_slice(if luma(px) >= level { &high } else { &low });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 5x3 black sprite with a white pixel in the middle
+    fn dot() -> Sprite {
+        let mut spr = Sprite::new_with_color(5, 3, Color::BLACK);
+        spr.set_pixel(2, 1, Color::WHITE);
+        spr
+    }
+
+    #[test]
+    fn gaussian_radius_is_clamped() {
+        assert_eq!(gaussian_weights(1.0, 10).len(), 7);
+        assert_eq!(gaussian_weights(1e30, 10).len(), 21);
+        assert_eq!(gaussian_weights(f32::INFINITY, 10), vec![1.0 / 21.0; 21]);
+        assert_eq!(
+            Kernel::gaussian(f32::MAX).width,
+            2 * MAX_KERNEL_RADIUS as usize + 1
+        );
+    }
+
+    #[test]
+    fn degenerate_sigma_is_identity() {
+        for sigma in [f32::NAN, 0.0, -1.0, f32::NEG_INFINITY] {
+            assert_eq!(gaussian_weights(sigma, 10), vec![0.0, 1.0, 0.0]);
+            let mut spr = dot();
+            spr.gaussian_blur(sigma);
+            assert_eq!(spr.get_pixel(2, 1), Color::WHITE, "{sigma}");
+            assert_eq!(spr.get_pixel(1, 1), Color::BLACK, "{sigma}");
+        }
+    }
+
+    #[test]
+    fn huge_blurs() {
+        let mut spr = dot();
+        spr.gaussian_blur(f32::INFINITY);
+        assert_eq!(spr.get_pixel(2, 1), spr.get_pixel(1, 1));
+        let mut spr = dot();
+        spr.gaussian_blur(f32::MAX);
+        spr.box_blur(u32::MAX);
+        assert_eq!(spr.get_pixel(0, 0).a, 255);
+    }
+}
@@ -0,0 +1,431 @@
+#![allow(clippy::cast_precision_loss)]
+//! Intersection and collision queries
+//!
+//! Casts (rays, segments and swept rectangles) return a [`Hit`] with the time of impact, the
+//! contact point and the surface normal. Overlap tests between shapes return a [`Contact`] with
+//! the smallest translation that separates them.
+
+use crate::rect::Rectf;
+use crate::vector2::Vf2d;
+
+const ZERO: Vf2d = Vf2d { x: 0.0, y: 0.0 };
+
+/// The result of a cast
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    /// Time of impact, the hit happens at `origin + dir * t`
+    pub t: f32,
+    /// Point of contact
+    pub point: Vf2d,
+    /// Normal of the surface that was hit, facing the incoming direction
+    ///
+    /// This is `(0, 0)` when the cast started inside of the shape
+    pub normal: Vf2d,
+}
+
+/// The result of an overlap test between two shapes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    /// Point of contact
+    pub point: Vf2d,
+    /// Direction to push the first shape out of the second one (normalized)
+    pub normal: Vf2d,
+    /// Distance to push the first shape out of the second one
+    pub depth: f32,
+}
+
+/// A half-line starting at `origin` and going towards `dir`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    /// Start of the ray
+    pub origin: Vf2d,
+    /// Direction of the ray, the times of impact are expressed in lengths of `dir`
+    pub dir: Vf2d,
+}
+
+/// A line segment between two points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    /// First end
+    pub start: Vf2d,
+    /// Second end
+    pub end: Vf2d,
+}
+
+/// A circle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    /// Center of the circle
+    pub center: Vf2d,
+    /// Radius of the circle
+    pub radius: f32,
+}
+
+/// Return `normal` facing against `dir`
+fn facing(normal: Vf2d, dir: Vf2d) -> Vf2d {
+    if normal.dot(&dir) > 0.0 {
+        -normal
+    } else {
+        normal
+    }
+}
+
+impl Ray {
+    /// Create a new [`Ray`]
+    #[must_use]
+    pub const fn new(origin: Vf2d, dir: Vf2d) -> Self {
+        Self { origin, dir }
+    }
+
+    /// Return the point at time `t` along the ray
+    #[must_use]
+    pub fn at(&self, t: f32) -> Vf2d {
+        self.origin + self.dir * t
+    }
+
+    /// Cast the ray against a segment
+    #[must_use]
+    pub fn cast_segment(&self, seg: &Segment) -> Option<Hit> {
+        let edge = seg.end - seg.start;
+        let denom = self.dir.cross(&edge);
+        if denom.abs() <= f32::EPSILON {
+            return None;
+        }
+        let diff = seg.start - self.origin;
+        let t = diff.cross(&edge) / denom;
+        let u = diff.cross(&self.dir) / denom;
+        if t < 0.0 || !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        Some(Hit {
+            t,
+            point: self.at(t),
+            normal: facing(edge.perp().norm(), self.dir),
+        })
+    }
+
+    /// Cast the ray against a rectangle
+    #[must_use]
+    pub fn cast_rect(&self, rect: &Rectf) -> Option<Hit> {
+        let (t_near, _, normal) = self.slabs(rect, false)?;
+        if t_near < 0.0 {
+            return Some(Hit {
+                t: 0.0,
+                point: self.origin,
+                normal: ZERO,
+            });
+        }
+        Some(Hit {
+            t: t_near,
+            point: self.at(t_near),
+            normal,
+        })
+    }
+
+    /// Return the entry time, exit time and entry normal of the ray through the rectangle
+    ///
+    /// When `strict` is set, only touching the edges of the rectangle isn't a hit
+    fn slabs(&self, rect: &Rectf, strict: bool) -> Option<(f32, f32, Vf2d)> {
+        let (min, max) = (rect.min(), rect.max());
+        let mut t_near = f32::NEG_INFINITY;
+        let mut t_far = f32::INFINITY;
+        let mut normal = ZERO;
+        let axes = [
+            (
+                self.origin.x,
+                self.dir.x,
+                min.x,
+                max.x,
+                Vf2d { x: 1.0, y: 0.0 },
+            ),
+            (
+                self.origin.y,
+                self.dir.y,
+                min.y,
+                max.y,
+                Vf2d { x: 0.0, y: 1.0 },
+            ),
+        ];
+        for (o, d, lo, hi, axis) in axes {
+            if d == 0.0 {
+                let outside = if strict {
+                    o <= lo || o >= hi
+                } else {
+                    o < lo || o > hi
+                };
+                if outside {
+                    return None;
+                }
+                continue;
+            }
+            let (mut t0, mut t1) = ((lo - o) / d, (hi - o) / d);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if t0 > t_near {
+                t_near = t0;
+                normal = if d > 0.0 { -axis } else { axis };
+            }
+            t_far = t_far.min(t1);
+        }
+        let missed = if strict {
+            t_near >= t_far
+        } else {
+            t_near > t_far
+        };
+        if missed || t_far < 0.0 {
+            return None;
+        }
+        Some((t_near, t_far, normal))
+    }
+
+    /// Cast the ray against a circle
+    #[must_use]
+    pub fn cast_circle(&self, circle: &Circle) -> Option<Hit> {
+        // Solve |origin + dir * t - center| = radius
+        let offset = self.origin - circle.center;
+        let a = self.dir.dot(&self.dir);
+        let half_b = offset.dot(&self.dir);
+        let c = offset.dot(&offset) - circle.radius * circle.radius;
+        if c <= 0.0 {
+            return Some(Hit {
+                t: 0.0,
+                point: self.origin,
+                normal: ZERO,
+            });
+        }
+        let disc = half_b * half_b - a * c;
+        if a == 0.0 || disc < 0.0 {
+            return None;
+        }
+        let t = (-half_b - disc.sqrt()) / a;
+        if t < 0.0 {
+            return None;
+        }
+        let point = self.at(t);
+        Some(Hit {
+            t,
+            point,
+            normal: (point - circle.center).norm(),
+        })
+    }
+}
+
+impl Segment {
+    /// Create a new [`Segment`]
+    #[must_use]
+    pub const fn new(start: Vf2d, end: Vf2d) -> Self {
+        Self { start, end }
+    }
+
+    /// Return the [`Ray`] going from `start` to `end` in one unit of time
+    #[must_use]
+    pub fn as_ray(&self) -> Ray {
+        Ray::new(self.start, self.end - self.start)
+    }
+
+    /// Return the intersection with the other segment, `t` is in `[0.0; 1.0]` along `self`
+    #[must_use]
+    pub fn cast_segment(&self, other: &Segment) -> Option<Hit> {
+        self.as_ray().cast_segment(other).filter(|h| h.t <= 1.0)
+    }
+
+    /// Return the first intersection with the rectangle, `t` is in `[0.0; 1.0]` along `self`
+    #[must_use]
+    pub fn cast_rect(&self, rect: &Rectf) -> Option<Hit> {
+        self.as_ray().cast_rect(rect).filter(|h| h.t <= 1.0)
+    }
+
+    /// Return the first intersection with the circle, `t` is in `[0.0; 1.0]` along `self`
+    #[must_use]
+    pub fn cast_circle(&self, circle: &Circle) -> Option<Hit> {
+        self.as_ray().cast_circle(circle).filter(|h| h.t <= 1.0)
+    }
+}
+
+impl Circle {
+    /// Create a new [`Circle`]
+    #[must_use]
+    pub const fn new(center: Vf2d, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Return true if the point is inside of the circle
+    #[must_use]
+    pub fn contains(&self, point: Vf2d) -> bool {
+        (point - self.center).mag2() <= self.radius * self.radius
+    }
+
+    /// Return true if the circles overlap
+    #[must_use]
+    pub fn intersects_circle(&self, other: &Circle) -> bool {
+        let r = self.radius + other.radius;
+        (self.center - other.center).mag2() <= r * r
+    }
+
+    /// Return true if the circle and the rectangle overlap
+    #[must_use]
+    pub fn intersects_rect(&self, rect: &Rectf) -> bool {
+        let closest = self.center.clamp(&rect.min(), &rect.max());
+        self.contains(closest)
+    }
+
+    /// Test the circle against a rectangle, the [`Contact`] pushes the circle out of the
+    /// rectangle
+    #[must_use]
+    pub fn collide_rect(&self, rect: &Rectf) -> Option<Contact> {
+        let (min, max) = (rect.min(), rect.max());
+        let closest = self.center.clamp(&min, &max);
+        let diff = self.center - closest;
+        let dist2 = diff.mag2();
+        if dist2 > self.radius * self.radius {
+            return None;
+        }
+        if dist2 > 0.0 {
+            let dist = dist2.sqrt();
+            return Some(Contact {
+                point: closest,
+                normal: diff / dist,
+                depth: self.radius - dist,
+            });
+        }
+        // The center is inside of the rectangle, push it out through the closest edge
+        let c = self.center;
+        let (dist, normal, point) = [
+            (
+                c.x - min.x,
+                Vf2d { x: -1.0, y: 0.0 },
+                Vf2d { x: min.x, y: c.y },
+            ),
+            (
+                max.x - c.x,
+                Vf2d { x: 1.0, y: 0.0 },
+                Vf2d { x: max.x, y: c.y },
+            ),
+            (
+                c.y - min.y,
+                Vf2d { x: 0.0, y: -1.0 },
+                Vf2d { x: c.x, y: min.y },
+            ),
+            (
+                max.y - c.y,
+                Vf2d { x: 0.0, y: 1.0 },
+                Vf2d { x: c.x, y: max.y },
+            ),
+        ]
+        .into_iter()
+        .min_by(|a, b| a.0.total_cmp(&b.0))?;
+        Some(Contact {
+            point,
+            normal,
+            depth: self.radius + dist,
+        })
+    }
+}
+
+/// Return true if the point is inside of the polygon (even-odd rule)
+///
+/// The polygon can be concave, its vertices are given in order and the last one is connected to
+/// the first one
+#[must_use]
+pub fn point_in_polygon(point: Vf2d, polygon: &[Vf2d]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for (i, &a) in polygon.iter().enumerate() {
+        let b = polygon[j];
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Return the smallest and largest projection of the polygon on the axis
+fn project(polygon: &[Vf2d], axis: Vf2d) -> (f32, f32) {
+    polygon
+        .iter()
+        .map(|p| p.dot(&axis))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        })
+}
+
+/// Iterate over the edges of the polygon
+fn edges(polygon: &[Vf2d]) -> impl Iterator<Item = Vf2d> + '_ {
+    let n = polygon.len();
+    (0..n).map(move |i| polygon[(i + 1) % n] - polygon[i])
+}
+
+/// Return the average of the vertices
+fn centroid(polygon: &[Vf2d]) -> Vf2d {
+    polygon.iter().fold(ZERO, |acc, &p| acc + p) / polygon.len() as f32
+}
+
+/// Test two convex polygons with the separating axis theorem
+///
+/// The [`Contact`] pushes `a` out of `b`, its point is the vertex of `a` the deepest inside of
+/// `b`. Returns `None` if the polygons don't overlap or if one of them has less than 3 vertices.
+#[must_use]
+pub fn polygons_collide(a: &[Vf2d], b: &[Vf2d]) -> Option<Contact> {
+    if a.len() < 3 || b.len() < 3 {
+        return None;
+    }
+    let mut depth = f32::INFINITY;
+    let mut normal = ZERO;
+    for edge in edges(a).chain(edges(b)) {
+        if edge.mag2() <= f32::EPSILON {
+            continue;
+        }
+        let axis = edge.perp().norm();
+        let (min_a, max_a) = project(a, axis);
+        let (min_b, max_b) = project(b, axis);
+        let overlap = max_a.min(max_b) - min_a.max(min_b);
+        if overlap <= 0.0 {
+            return None;
+        }
+        if overlap < depth {
+            depth = overlap;
+            normal = axis;
+        }
+    }
+    if (centroid(a) - centroid(b)).dot(&normal) < 0.0 {
+        normal = -normal;
+    }
+    let point = a
+        .iter()
+        .copied()
+        .min_by(|p, q| p.dot(&normal).total_cmp(&q.dot(&normal)))?;
+    Some(Contact {
+        point,
+        normal,
+        depth,
+    })
+}
+
+/// Sweep the `moving` rectangle by `velocity` against the `target` rectangle
+///
+/// `t` is in `[0.0; 1.0]`, the fraction of the movement done before the impact, and `point` is
+/// the position of the moving rectangle at that time. If the rectangles already overlap, the hit
+/// is at `t = 0.0` with a `(0, 0)` normal. Sliding along an edge of the target isn't a hit.
+#[must_use]
+pub fn sweep_rect(moving: &Rectf, velocity: Vf2d, target: &Rectf) -> Option<Hit> {
+    if moving.intersects(target) {
+        return Some(Hit {
+            t: 0.0,
+            point: moving.pos,
+            normal: ZERO,
+        });
+    }
+    // Casting the corner against the target grown by the size of the moving rectangle
+    let expanded = Rectf::new(target.pos - moving.size, target.size + moving.size);
+    let ray = Ray::new(moving.pos, velocity);
+    let (t, _, normal) = ray.slabs(&expanded, true)?;
+    (0.0..=1.0).contains(&t).then(|| Hit {
+        t,
+        point: ray.at(t),
+        normal,
+    })
+}
@@ -9,6 +9,7 @@ use std::{cell::UnsafeCell, convert::TryInto, mem::transmute, sync::Arc};
 use once_cell::sync::OnceCell;
 use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
 
+use crate::rect::{Recti, Rectu};
 use crate::vector2::{Vi2d, Vu2d};
 
 /// The Drawing Mode used
@@ -49,21 +50,13 @@ impl std::clone::Clone for Sprite {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct Area {
-    pub pos: Vu2d,
-    pub size: Vu2d,
-}
-
-impl Area {
-    fn overlap(&self, other: &Self) -> bool {
-        (self.pos.x < other.pos.x && other.pos.x < self.pos.x + self.size.x)
-            || (self.pos.y < other.pos.y && other.pos.y < self.pos.y + self.size.y)
-            || (other.pos.x < self.pos.x && self.pos.x < other.pos.x + other.size.x)
-            || (other.pos.y < self.pos.y && self.pos.y < other.pos.y + other.size.y)
-    }
-}
+/// The part of a [`Sprite`] covered by a subsprite
+pub type Area = Rectu;
 
+/// A mutable view into a part of a [`Sprite`]
+///
+/// Multiple [`SpriteMutRef`] of the same sprite never overlap, so they can be sent to different
+/// threads and be drawn on at the same time.
 #[derive(Debug)]
 pub struct SpriteMutRef<'spr> {
     /// A reference to the base sprite;
@@ -177,13 +170,13 @@ impl Sprite {
     ) -> Result<SpriteMutRef<'_>, OverlappingError> {
         let real_pos = Vu2d {
             x: if pos.x < 0 {
-                size.x -= (-pos.x) as u32;
+                size.x = size.x.saturating_sub(pos.x.unsigned_abs());
                 0
             } else {
                 (pos.x as u32).min(self.size.x)
             },
             y: if pos.y < 0 {
-                size.y -= (-pos.y) as u32;
+                size.y = size.y.saturating_sub(pos.y.unsigned_abs());
                 0
             } else {
                 (pos.y as u32).min(self.size.y)
@@ -199,7 +192,7 @@ impl Sprite {
         };
         let mut lock = self.areas.lock();
 
-        let overlap = lock.iter().any(|(_, a)| a.overlap(&area));
+        let overlap = lock.iter().any(|(_, a)| a.intersects(&area));
 
         if overlap {
             return Err(OverlappingError);
@@ -214,6 +207,92 @@ impl Sprite {
         unsafe { Ok(self.create_sub_sprite_unchecked(real_pos, size, index)) }
     }
 
+    /// Same as [`Sprite::create_sub_sprite`] but with a [`Rect`](crate::rect::Rect), a negative
+    /// size is treated as 0
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if an subsprite already exists and that it overlaps
+    pub fn create_sub_sprite_area(
+        &self,
+        area: Recti,
+    ) -> Result<SpriteMutRef<'_>, OverlappingError> {
+        self.create_sub_sprite(
+            area.pos,
+            Vu2d {
+                x: area.size.x.max(0) as u32,
+                y: area.size.y.max(0) as u32,
+            },
+        )
+    }
+
+    /// Split the sprite into horizontal bands of `band_height` rows, the last band being
+    /// smaller if the height isn't a multiple of `band_height`
+    ///
+    /// Each band is a [`SpriteMutRef`] that can be moved to another thread, for example one band
+    /// per thread with `band_height = height.div_ceil(thread_count)`
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if a subsprite of this sprite already exists
+    pub fn split_rows(&self, band_height: u32) -> Result<Vec<SpriteMutRef<'_>>, OverlappingError> {
+        self.split_tiles(Vu2d {
+            x: self.size.x,
+            y: band_height,
+        })
+    }
+
+    /// Split the sprite into a grid of tiles of `tile_size`, the tiles on the right and bottom
+    /// edges being smaller if the size isn't a multiple of `tile_size`
+    ///
+    /// The tiles are ordered row by row
+    ///
+    /// # Errors
+    ///
+    /// This will return an error if a subsprite of this sprite already exists
+    pub fn split_tiles(&self, tile_size: Vu2d) -> Result<Vec<SpriteMutRef<'_>>, OverlappingError> {
+        let tile_w = tile_size.x.max(1);
+        let tile_h = tile_size.y.max(1);
+        let areas = (0..self.size.y).step_by(tile_h as usize).flat_map(|y| {
+            (0..self.size.x)
+                .step_by(tile_w as usize)
+                .map(move |x| Area {
+                    pos: Vu2d { x, y },
+                    size: Vu2d {
+                        x: tile_w.min(self.size.x - x),
+                        y: tile_h.min(self.size.y - y),
+                    },
+                })
+        });
+        self.create_sub_sprites(areas)
+    }
+
+    /// Create multiple subsprites at once, either all of them are created or none
+    fn create_sub_sprites(
+        &self,
+        areas: impl Iterator<Item = Area>,
+    ) -> Result<Vec<SpriteMutRef<'_>>, OverlappingError> {
+        let mut lock = self.areas.lock();
+        let areas: Vec<Area> = areas.collect();
+        if areas
+            .iter()
+            .any(|area| lock.iter().any(|(_, a)| a.intersects(area)))
+        {
+            return Err(OverlappingError);
+        }
+        Ok(areas
+            .into_iter()
+            .map(|area| {
+                let index = lock.insert(area);
+                // SAFETY:
+                // The areas are generated inside the main sprite and never overlap each other
+                // We checked that there is no overlap with the existing subsprites
+                // We added the area to the list
+                unsafe { self.create_sub_sprite_unchecked(area.pos, area.size, index) }
+            })
+            .collect())
+    }
+
     /// Creates a subsprite without any checks
     ///
     /// # Safety
@@ -354,6 +433,9 @@ impl Sprite {
     /// Return the [Color] of the pixel at given sample
     /// This sampling will take only the fractional part of the given sample coordinates, so it is
     /// effictivly from [O; 1)
+    ///
+    /// Use [`Sprite::sample`] with a [`Sampler`](crate::sampler::Sampler) for filtering and
+    /// other address modes
     pub fn get_sample(&self, x: f64, y: f64) -> Color {
         let x = x.fract();
         let y = y.fract();
@@ -364,6 +446,18 @@ impl Sprite {
 }
 
 impl<'spr> SpriteMutRef<'spr> {
+    /// Return the position of the top left corner of the subsprite inside the base sprite
+    #[must_use]
+    pub fn pos(&self) -> Vu2d {
+        self.pos
+    }
+
+    /// Return the part of the base sprite covered by the subsprite
+    #[must_use]
+    pub fn area(&self) -> Area {
+        Area::new(self.pos, self.size)
+    }
+
     fn get_nth_ptr(&self, row: u32) -> *mut u8 {
         unsafe {
             let base_offset = (self.pos.y * self.spr_width + self.pos.x) as usize * 4;
@@ -903,3 +997,90 @@ impl From<Color> for [f32; 3] {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(
+        spr: &Sprite,
+        pos: (i32, i32),
+        size: (u32, u32),
+    ) -> Result<SpriteMutRef<'_>, OverlappingError> {
+        spr.create_sub_sprite(Vi2d::new(pos.0, pos.1), Vu2d::new(size.0, size.1))
+    }
+
+    #[test]
+    fn disjoint_sub_sprites() {
+        let spr = Sprite::new(8, 8);
+        // Sharing rows or columns isn't an overlap
+        let _a = sub(&spr, (0, 0), (2, 2)).unwrap();
+        let _b = sub(&spr, (4, 1), (2, 2)).unwrap();
+        let _c = sub(&spr, (1, 4), (2, 2)).unwrap();
+        // Touching edges isn't an overlap
+        let _d = sub(&spr, (2, 0), (2, 1)).unwrap();
+        let _e = sub(&spr, (6, 6), (2, 2)).unwrap();
+    }
+
+    #[test]
+    fn overlapping_sub_sprites() {
+        let spr = Sprite::new(8, 8);
+        let _a = sub(&spr, (2, 2), (4, 4)).unwrap();
+        // Same area
+        assert!(sub(&spr, (2, 2), (4, 4)).is_err());
+        // Inside
+        assert!(sub(&spr, (3, 3), (1, 1)).is_err());
+        // Around
+        assert!(sub(&spr, (0, 0), (8, 8)).is_err());
+        // Corners
+        assert!(sub(&spr, (0, 0), (3, 3)).is_err());
+        assert!(sub(&spr, (5, 5), (3, 3)).is_err());
+        // Crossing
+        assert!(sub(&spr, (0, 3), (8, 1)).is_err());
+    }
+
+    #[test]
+    fn empty_and_clipped_sub_sprites() {
+        let spr = Sprite::new(8, 8);
+        let _a = sub(&spr, (0, 0), (8, 8)).unwrap();
+        // An empty area never overlaps
+        let _b = sub(&spr, (4, 4), (0, 3)).unwrap();
+        // Clipped away by a negative position, without underflowing
+        let c = sub(&spr, (-5, 2), (3, 3)).unwrap();
+        assert_eq!(DrawSpriteTrait::size(&c), Vu2d::new(0, 3));
+    }
+
+    #[test]
+    fn dropped_sub_sprite_frees_its_area() {
+        let spr = Sprite::new(4, 4);
+        let a = sub(&spr, (0, 0), (4, 4)).unwrap();
+        assert!(sub(&spr, (1, 1), (1, 1)).is_err());
+        drop(a);
+        assert!(sub(&spr, (1, 1), (1, 1)).is_ok());
+    }
+
+    #[test]
+    fn split_tiles() {
+        let spr = Sprite::new(5, 3);
+        let tiles = spr.split_tiles(Vu2d::new(2, 2)).unwrap();
+        let areas: Vec<_> = tiles
+            .iter()
+            .map(|t| (t.pos(), DrawSpriteTrait::size(t)))
+            .collect();
+        assert_eq!(
+            areas,
+            vec![
+                (Vu2d::new(0, 0), Vu2d::new(2, 2)),
+                (Vu2d::new(2, 0), Vu2d::new(2, 2)),
+                (Vu2d::new(4, 0), Vu2d::new(1, 2)),
+                (Vu2d::new(0, 2), Vu2d::new(2, 1)),
+                (Vu2d::new(2, 2), Vu2d::new(2, 1)),
+                (Vu2d::new(4, 2), Vu2d::new(1, 1)),
+            ]
+        );
+        assert!(sub(&spr, (4, 2), (1, 1)).is_err());
+        drop(tiles);
+        let _a = sub(&spr, (4, 2), (1, 1)).unwrap();
+        assert!(spr.split_rows(1).is_err());
+    }
+}
@@ -4,6 +4,15 @@ extern crate paste;
 extern crate parking_lot;
 extern crate slab;
 
+pub mod color;
+pub mod filters;
+pub mod geometry;
 pub mod graphics;
+pub mod palette;
+pub mod quantize;
+pub mod raycast;
+pub mod rect;
+pub mod sampler;
+pub mod shader;
 pub mod traits;
 pub mod vector2;
@@ -0,0 +1,497 @@
+#![allow(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::unreadable_literal
+)]
+//! Indexed-color sprites
+//!
+//! An [`IndexedSprite`] stores one byte per pixel, each byte being an index into a [`Palette`] of
+//! up to 256 [`Color`]s. Swapping or cycling the palette changes the look of the sprite without
+//! touching the pixel data.
+
+use crate::graphics::{Color, Sprite};
+use crate::vector2::Vu2d;
+
+/// The maximum number of colors a [`Palette`] can hold
+pub const MAX_PALETTE_SIZE: usize = 256;
+
+/// A list of up to 256 [`Color`]s
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Palette {
+    colors: Vec<Color>,
+}
+
+impl Palette {
+    /// Create an empty [`Palette`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            colors: Vec::with_capacity(16),
+        }
+    }
+
+    /// Create a [`Palette`] from the given colors
+    ///
+    /// # Panics
+    ///
+    /// If there is more than 256 colors
+    #[must_use]
+    pub fn from_colors(colors: &[Color]) -> Self {
+        assert!(
+            colors.len() <= MAX_PALETTE_SIZE,
+            "A palette can't have more than {MAX_PALETTE_SIZE} colors"
+        );
+        Self {
+            colors: colors.to_vec(),
+        }
+    }
+
+    /// Return the number of colors in the palette
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Return true if the palette doesn't have any color
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Return a view of all the colors
+    #[must_use]
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Return the [`Color`] at the given index
+    /// Index outside of the palette will return [`Color::BLANK`]
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: u8) -> Color {
+        self.colors
+            .get(index as usize)
+            .copied()
+            .unwrap_or(Color::BLANK)
+    }
+
+    /// Set the [`Color`] at the given index, does nothing if the index is outside of the palette
+    pub fn set(&mut self, index: u8, col: Color) {
+        if let Some(c) = self.colors.get_mut(index as usize) {
+            *c = col;
+        }
+    }
+
+    /// Add a color at the end of the palette and return its index
+    /// Returns `None` if the palette is already full
+    pub fn push(&mut self, col: Color) -> Option<u8> {
+        if self.colors.len() >= MAX_PALETTE_SIZE {
+            return None;
+        }
+        self.colors.push(col);
+        Some((self.colors.len() - 1) as u8)
+    }
+
+    /// Rotate the colors in the range `first..=last` by `shift` steps
+    ///
+    /// A positive shift moves every color towards the end of the range, the last ones wrapping
+    /// around to the start. The parts of the range outside of the palette are ignored.
+    pub fn cycle(&mut self, first: u8, last: u8, shift: i32) {
+        let (first, last) = (first.min(last) as usize, first.max(last) as usize);
+        if first >= self.colors.len() {
+            return;
+        }
+        let last = last.min(self.colors.len() - 1);
+        let range = &mut self.colors[first..=last];
+        let shift = shift.rem_euclid(range.len() as i32) as usize;
+        range.rotate_right(shift);
+    }
+}
+
+/// Convert a list of `0xRRGGBB` values into colors
+const fn rgb_list<const N: usize>(list: [u32; N]) -> [Color; N] {
+    let mut out = [Color::BLACK; N];
+    let mut i = 0;
+    while i < N {
+        let [_, r, g, b] = list[i].to_be_bytes();
+        out[i] = Color::new(r, g, b);
+        i += 1;
+    }
+    out
+}
+
+/// The 16 colors of the PICO-8 fantasy console
+pub const PICO8_COLORS: [Color; 16] = rgb_list([
+    0x000000, 0x1D2B53, 0x7E2553, 0x008751, 0xAB5236, 0x5F574F, 0xC2C3C7, 0xFFF1E8, 0xFF004D,
+    0xFFA300, 0xFFEC27, 0x00E436, 0x29ADFF, 0x83769C, 0xFF77A8, 0xFFCCAA,
+]);
+
+/// The 64 entries of the NES (2C02) palette, in hardware order
+pub const NES_COLORS: [Color; 64] = rgb_list([
+    0x7C7C7C, 0x0000FC, 0x0000BC, 0x4428BC, 0x940084, 0xA80020, 0xA81000, 0x881400, 0x503000,
+    0x007800, 0x006800, 0x005800, 0x004058, 0x000000, 0x000000, 0x000000, 0xBCBCBC, 0x0078F8,
+    0x0058F8, 0x6844FC, 0xD800CC, 0xE40058, 0xF83800, 0xE45C10, 0xAC7C00, 0x00B800, 0x00A800,
+    0x00A844, 0x008888, 0x000000, 0x000000, 0x000000, 0xF8F8F8, 0x3CBCFC, 0x6888FC, 0x9878F8,
+    0xF878F8, 0xF85898, 0xF87858, 0xFCA044, 0xF8B800, 0xB8F818, 0x58D854, 0x58F898, 0x00E8D8,
+    0x787878, 0x000000, 0x000000, 0xFCFCFC, 0xA4E4FC, 0xB8B8F8, 0xD8B8F8, 0xF8B8F8, 0xF8A4C0,
+    0xF0D0B0, 0xFCE0A8, 0xF8D878, 0xD8F878, 0xB8F8B8, 0xB8F8D8, 0x00FCFC, 0xF8D8F8, 0x000000,
+    0x000000,
+]);
+
+/// The 4 shades of green of the original Game Boy, from the darkest to the lightest
+pub const GAME_BOY_COLORS: [Color; 4] = rgb_list([0x0F380F, 0x306230, 0x8BAC0F, 0x9BBC0F]);
+
+impl Palette {
+    /// The PICO-8 palette, see [`PICO8_COLORS`]
+    #[must_use]
+    pub fn pico8() -> Self {
+        Self::from_colors(&PICO8_COLORS)
+    }
+
+    /// The NES palette, see [`NES_COLORS`]
+    #[must_use]
+    pub fn nes() -> Self {
+        Self::from_colors(&NES_COLORS)
+    }
+
+    /// The Game Boy palette, see [`GAME_BOY_COLORS`]
+    #[must_use]
+    pub fn game_boy() -> Self {
+        Self::from_colors(&GAME_BOY_COLORS)
+    }
+
+    /// Return the index of the color of the palette closest to `col` (ignoring alpha)
+    /// Returns `None` if the palette is empty
+    #[must_use]
+    pub fn nearest(&self, col: Color) -> Option<u8> {
+        self.nearest_rgb([f32::from(col.r), f32::from(col.g), f32::from(col.b)])
+    }
+
+    /// Same as [`Palette::nearest`] with channels in `[0.0; 255.0]`
+    pub(crate) fn nearest_rgb(&self, rgb: [f32; 3]) -> Option<u8> {
+        self.colors
+            .iter()
+            .map(|c| {
+                let dr = f32::from(c.r) - rgb[0];
+                let dg = f32::from(c.g) - rgb[1];
+                let db = f32::from(c.b) - rgb[2];
+                dr * dr + dg * dg + db * db
+            })
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i as u8)
+    }
+
+    /// Load a palette file, the format is chosen from the extension:
+    /// - `.gpl`: GIMP palette
+    /// - `.hex`: one `rrggbb` color per line
+    /// - `.pal`: JASC palette
+    ///
+    /// # Errors
+    ///
+    /// If the file IO failed, if the extension isn't one of the above or if the file is invalid
+    pub fn load_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .map(str::to_ascii_lowercase);
+        let parse = match ext.as_deref() {
+            Some("gpl") => Self::from_gpl,
+            Some("hex") => Self::from_hex_list,
+            Some("pal") => Self::from_jasc_pal,
+            _ => return Err(format!("Unknown palette format: {}", path.display())),
+        };
+        parse(&std::fs::read_to_string(path).map_err(|err| err.to_string())?)
+    }
+
+    /// Parse a GIMP palette (`.gpl`)
+    ///
+    /// # Errors
+    ///
+    /// If the header is missing, if a color line is invalid or if there is more than 256 colors
+    pub fn from_gpl(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some("GIMP Palette") {
+            return Err("Missing `GIMP Palette` header".to_string());
+        }
+        let colors = lines
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .filter(|l| !l.starts_with("Name:") && !l.starts_with("Columns:"))
+            .map(parse_rgb_line)
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::checked(colors)
+    }
+
+    /// Parse a list of hex colors (`.hex`), one `rrggbb` per line, the `#` is optional
+    ///
+    /// # Errors
+    ///
+    /// If a line isn't a valid color or if there is more than 256 colors
+    pub fn from_hex_list(text: &str) -> Result<Self, String> {
+        let colors = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| Color::from_hex(l).map_err(|err| format!("{err}: `{l}`")))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::checked(colors)
+    }
+
+    /// Parse a JASC palette (`.pal`)
+    ///
+    /// # Errors
+    ///
+    /// If the header is missing, if a color line is invalid, if the number of colors doesn't
+    /// match the header or if there is more than 256 colors
+    pub fn from_jasc_pal(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+        if lines.next() != Some("JASC-PAL") || lines.next().is_none() {
+            return Err("Missing `JASC-PAL` header".to_string());
+        }
+        let count: usize = lines
+            .next()
+            .and_then(|l| l.parse().ok())
+            .ok_or_else(|| "Missing color count".to_string())?;
+        let colors = lines.map(parse_rgb_line).collect::<Result<Vec<_>, _>>()?;
+        if colors.len() != count {
+            return Err(format!("Expected {count} colors, found {}", colors.len()));
+        }
+        Self::checked(colors)
+    }
+
+    fn checked(colors: Vec<Color>) -> Result<Self, String> {
+        if colors.len() > MAX_PALETTE_SIZE {
+            return Err(format!(
+                "A palette can't have more than {MAX_PALETTE_SIZE} colors"
+            ));
+        }
+        Ok(Self { colors })
+    }
+}
+
+/// Parse a `R G B [name]` line with decimal channels
+fn parse_rgb_line(line: &str) -> Result<Color, String> {
+    let mut channels = line.split_whitespace().map(|c| {
+        c.parse::<u8>()
+            .map_err(|_| format!("Invalid color: `{line}`"))
+    });
+    let mut next = || {
+        channels
+            .next()
+            .unwrap_or_else(|| Err(format!("Invalid color: `{line}`")))
+    };
+    Ok(Color::new(next()?, next()?, next()?))
+}
+
+impl From<Vec<Color>> for Palette {
+    /// # Panics
+    ///
+    /// If there is more than 256 colors
+    fn from(colors: Vec<Color>) -> Self {
+        assert!(
+            colors.len() <= MAX_PALETTE_SIZE,
+            "A palette can't have more than {MAX_PALETTE_SIZE} colors"
+        );
+        Self { colors }
+    }
+}
+
+/// Animate a range of a [`Palette`] by cycling it at a fixed speed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteCycle {
+    /// First index of the cycled range
+    pub first: u8,
+    /// Last index of the cycled range (inclusive)
+    pub last: u8,
+    /// Number of steps per second, a negative speed cycles the other way
+    pub speed: f64,
+    timer: f64,
+}
+
+impl PaletteCycle {
+    /// Create a new [`PaletteCycle`] for the range `first..=last`
+    #[must_use]
+    pub const fn new(first: u8, last: u8, speed: f64) -> Self {
+        Self {
+            first,
+            last,
+            speed,
+            timer: 0.0,
+        }
+    }
+
+    /// Advance the animation by `elapsed` seconds, cycling the palette when needed
+    ///
+    /// Returns true if the palette was modified
+    pub fn update(&mut self, palette: &mut Palette, elapsed: f64) -> bool {
+        self.timer += elapsed * self.speed;
+        let steps = self.timer.trunc();
+        if steps == 0.0 {
+            return false;
+        }
+        self.timer -= steps;
+        palette.cycle(self.first, self.last, steps as i32);
+        true
+    }
+}
+
+/// A sprite where every pixel is an index into a [`Palette`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedSprite {
+    size: Vu2d,
+    data: Box<[u8]>,
+    palette: Palette,
+}
+
+impl IndexedSprite {
+    /// Create an [`IndexedSprite`] where every pixel is at index 0
+    #[must_use]
+    pub fn new(w: u32, h: u32, palette: Palette) -> Self {
+        Self {
+            size: Vu2d { x: w, y: h },
+            data: vec![0; w as usize * h as usize].into_boxed_slice(),
+            palette,
+        }
+    }
+
+    /// Create an [`IndexedSprite`] from a slice of indices, will clone the slice
+    ///
+    /// # Errors
+    ///
+    /// If `indices.len() != width * height`
+    pub fn from_indices(
+        indices: &[u8],
+        width: u32,
+        height: u32,
+        palette: Palette,
+    ) -> Result<Self, String> {
+        if indices.len() != width as usize * height as usize {
+            return Err("Wrong Image len".to_string());
+        }
+        Ok(Self {
+            size: Vu2d {
+                x: width,
+                y: height,
+            },
+            data: indices.into(),
+            palette,
+        })
+    }
+
+    /// Return the size of the sprite
+    #[must_use]
+    pub fn size(&self) -> Vu2d {
+        self.size
+    }
+
+    /// Return the width of the sprite
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.size.x
+    }
+
+    /// Return the height of the sprite
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.size.y
+    }
+
+    /// Return the raw indices, row by row
+    #[must_use]
+    pub fn indices(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Return the raw indices, row by row
+    pub fn indices_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// Return the [`Palette`] used by the sprite
+    #[must_use]
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Return the [`Palette`] used by the sprite
+    pub fn palette_mut(&mut self) -> &mut Palette {
+        &mut self.palette
+    }
+
+    /// Replace the [`Palette`] of the sprite and return the old one
+    pub fn set_palette(&mut self, palette: Palette) -> Palette {
+        std::mem::replace(&mut self.palette, palette)
+    }
+
+    /// Return the palette index of the given pixel, if it exist
+    #[must_use]
+    pub fn get_index(&self, x: u32, y: u32) -> Option<u8> {
+        if x >= self.size.x || y >= self.size.y {
+            return None;
+        }
+        Some(self.data[(y * self.size.x + x) as usize])
+    }
+
+    /// Set the palette index of the given pixel
+    pub fn set_index(&mut self, x: u32, y: u32, index: u8) {
+        if x >= self.size.x || y >= self.size.y {
+            return;
+        }
+        self.data[(y * self.size.x + x) as usize] = index;
+    }
+
+    /// Return the [`Color`] of the pixel at the given coordinates using the sprite's palette
+    /// Out of bounds pixels will return [`Color::BLANK`]
+    #[must_use]
+    pub fn get_pixel(&self, x: u32, y: u32) -> Color {
+        self.get_index(x, y)
+            .map_or(Color::BLANK, |i| self.palette.get(i))
+    }
+
+    /// Fill the whole sprite with the given index
+    pub fn clear(&mut self, index: u8) {
+        self.data.fill(index);
+    }
+
+    /// Convert into an RGBA [`Sprite`] using the sprite's palette
+    #[must_use]
+    pub fn to_sprite(&self) -> Sprite {
+        self.to_sprite_with_palette(&self.palette)
+    }
+
+    /// Convert into an RGBA [`Sprite`] using the given palette instead of the sprite's one
+    #[must_use]
+    pub fn to_sprite_with_palette(&self, palette: &Palette) -> Sprite {
+        let mut spr = Sprite::new(self.size.x, self.size.y);
+        self.write_to_sprite_with_palette(&mut spr, palette);
+        spr
+    }
+
+    /// Write the colors into an existing [`Sprite`], this avoids an allocation when the
+    /// palette is animated every frame
+    ///
+    /// Only the part where both sprites overlap is written
+    pub fn write_to_sprite(&self, spr: &mut Sprite) {
+        self.write_to_sprite_with_palette(spr, &self.palette);
+    }
+
+    /// Same as [`IndexedSprite::write_to_sprite`] but with the given palette
+    pub fn write_to_sprite_with_palette(&self, spr: &mut Sprite, palette: &Palette) {
+        let w = self.size.x.min(spr.width());
+        let h = self.size.y.min(spr.height());
+        let spr_width = spr.width() as usize;
+        let (raw, lock) = spr.get_read_lock();
+        for y in 0..h as usize {
+            let src = &self.data[y * self.size.x as usize..][..w as usize];
+            let dst = &mut raw[y * spr_width * 4..][..w as usize * 4];
+            for (&index, px) in src.iter().zip(dst.chunks_exact_mut(4)) {
+                px.copy_from_slice(&<[u8; 4]>::from(palette.get(index)));
+            }
+        }
+        drop(lock);
+    }
+}
@@ -0,0 +1,137 @@
+#![allow(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss
+)]
+//! Reduce [`Sprite`]s to the colors of a [`Palette`]
+//!
+//! Every pixel is replaced by the closest color of the palette, optionally with dithering to
+//! hide the banding.
+
+use crate::graphics::Sprite;
+use crate::palette::{IndexedSprite, Palette};
+
+/// How the quantization error is spread between pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Dithering {
+    /// Every pixel takes the closest color
+    #[default]
+    None,
+    /// Floyd–Steinberg error diffusion, spreads all the error to the neighbouring pixels
+    FloydSteinberg,
+    /// Atkinson error diffusion, only spreads 3/4 of the error which keeps more contrast
+    Atkinson,
+    /// 4x4 Bayer matrix, a regular pattern that stays stable when the image is animated
+    Ordered,
+}
+
+/// 4x4 Bayer threshold matrix
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Error diffusion kernels as `(dx, dy, weight)`, and the weights' denominator
+const FLOYD_STEINBERG: (&[(i32, i32, f32)], f32) =
+    (&[(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)], 16.0);
+const ATKINSON: (&[(i32, i32, f32)], f32) = (
+    &[
+        (1, 0, 1.0),
+        (2, 0, 1.0),
+        (-1, 1, 1.0),
+        (0, 1, 1.0),
+        (1, 1, 1.0),
+        (0, 2, 1.0),
+    ],
+    8.0,
+);
+
+/// Return the palette index of every pixel of the sprite, row by row
+///
+/// # Panics
+///
+/// If the palette is empty
+fn quantize_indices(spr: &Sprite, palette: &Palette, dithering: Dithering) -> Vec<u8> {
+    assert!(!palette.is_empty(), "Can't quantize to an empty palette");
+    let (w, h) = (spr.width() as usize, spr.height() as usize);
+    let (raw, lock) = spr.get_read_lock();
+    let mut rgb: Vec<[f32; 3]> = raw
+        .chunks_exact(4)
+        .map(|px| [f32::from(px[0]), f32::from(px[1]), f32::from(px[2])])
+        .collect();
+    drop(lock);
+    let nearest = |c: [f32; 3]| palette.nearest_rgb(c).unwrap_or_default();
+
+    let diffusion = match dithering {
+        Dithering::None => return rgb.into_iter().map(nearest).collect(),
+        Dithering::Ordered => {
+            // Roughly the distance between two colors of the palette on each channel
+            let spread = 255.0 / (palette.len() as f32).cbrt();
+            return rgb
+                .into_iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    let t = f32::from(BAYER_4X4[(i / w) % 4][(i % w) % 4]) / 16.0 - 0.5;
+                    nearest(c.map(|v| v + t * spread))
+                })
+                .collect();
+        }
+        Dithering::FloydSteinberg => FLOYD_STEINBERG,
+        Dithering::Atkinson => ATKINSON,
+    };
+
+    let (kernel, denominator) = diffusion;
+    let mut out = Vec::with_capacity(w * h);
+    for y in 0..h {
+        for x in 0..w {
+            let old = rgb[y * w + x];
+            let index = nearest(old);
+            out.push(index);
+            let new = palette.get(index);
+            let err = [
+                old[0] - f32::from(new.r),
+                old[1] - f32::from(new.g),
+                old[2] - f32::from(new.b),
+            ];
+            for &(dx, dy, weight) in kernel {
+                let (nx, ny) = (x as i32 + dx, y + dy as usize);
+                if nx < 0 || nx as usize >= w || ny >= h {
+                    continue;
+                }
+                let px = &mut rgb[ny * w + nx as usize];
+                for c in 0..3 {
+                    px[c] += err[c] * weight / denominator;
+                }
+            }
+        }
+    }
+    out
+}
+
+impl Sprite {
+    /// Replace every pixel by the closest color of the palette
+    ///
+    /// Only the color channels are quantized, the alpha of each pixel is kept
+    ///
+    /// # Panics
+    ///
+    /// If the palette is empty
+    pub fn quantize(&mut self, palette: &Palette, dithering: Dithering) {
+        let indices = quantize_indices(self, palette, dithering);
+        let (raw, lock) = self.get_read_lock();
+        for (px, &index) in raw.chunks_exact_mut(4).zip(&indices) {
+            px[..3].copy_from_slice(&<[u8; 3]>::from(palette.get(index)));
+        }
+        drop(lock);
+    }
+
+    /// Create an [`IndexedSprite`] using the closest color of the palette for every pixel
+    ///
+    /// # Panics
+    ///
+    /// If the palette is empty
+    #[must_use]
+    pub fn to_indexed(&self, palette: Palette, dithering: Dithering) -> IndexedSprite {
+        let indices = quantize_indices(self, &palette, dithering);
+        IndexedSprite::from_indices(&indices, self.width(), self.height(), palette)
+            .expect("The sprite and the indices have the same size")
+    }
+}
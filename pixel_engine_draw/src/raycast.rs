@@ -0,0 +1,246 @@
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+//! Ray casting through a grid of square cells of size 1
+//!
+//! This is the DDA (digital differential analyzer) walk used by raycasting renderers and line of
+//! sight checks: the ray visits every cell it crosses, in order, until a solid one is found.
+
+use crate::vector2::{Vf2d, Vi2d};
+
+/// Biggest cell coordinate a [`GridRay`] goes to, past it a `f32` can't tell the cells apart
+const MAX_CELL: i32 = 1 << 24;
+
+/// The face of a cell, the y axis going down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The top face (`-y`)
+    North,
+    /// The right face (`+x`)
+    East,
+    /// The bottom face (`+y`)
+    South,
+    /// The left face (`-x`)
+    West,
+}
+
+impl Side {
+    /// Return the outward normal of the face
+    #[must_use]
+    pub const fn normal(self) -> Vi2d {
+        match self {
+            Side::North => Vi2d { x: 0, y: -1 },
+            Side::East => Vi2d { x: 1, y: 0 },
+            Side::South => Vi2d { x: 0, y: 1 },
+            Side::West => Vi2d { x: -1, y: 0 },
+        }
+    }
+
+    /// Return true for the [`Side::East`] and [`Side::West`] faces
+    #[must_use]
+    pub const fn is_vertical(self) -> bool {
+        matches!(self, Side::East | Side::West)
+    }
+}
+
+/// A solid cell found by a [`GridRay`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridHit {
+    /// The solid cell
+    pub cell: Vi2d,
+    /// The point where the ray entered the cell
+    pub point: Vf2d,
+    /// The face of the cell that was hit
+    pub side: Side,
+    /// The distance between the start and the hit point
+    pub distance: f32,
+    /// The distance in lengths of the ray's `dir`
+    ///
+    /// When `dir` is `camera_dir + camera_plane * x`, this is the distance to the camera plane
+    /// which doesn't have the fisheye effect of [`GridHit::distance`]
+    pub perp_distance: f32,
+}
+
+/// A ray going through a grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridRay {
+    /// Start of the ray, in cells
+    ///
+    /// The ray only walks through the cells with coordinates between `-2^24` and `2^24`, where a
+    /// `f32` can still tell them apart
+    pub start: Vf2d,
+    /// Direction of the ray, doesn't need to be normalized
+    pub dir: Vf2d,
+    /// The ray stops after this distance (in cells), nothing is found if it isn't finite or if it
+    /// is negative. Use `f32::MAX` for no limit
+    pub max_distance: f32,
+}
+
+impl GridRay {
+    /// Create a new [`GridRay`]
+    #[must_use]
+    pub const fn new(start: Vf2d, dir: Vf2d, max_distance: f32) -> Self {
+        Self {
+            start,
+            dir,
+            max_distance,
+        }
+    }
+
+    /// Walk the grid until `is_solid` returns true for a cell
+    ///
+    /// The cell containing the start isn't tested. Returns `None` if no solid cell is found
+    /// before `max_distance`, if `dir` is `(0, 0)`, or if `max_distance` or the ray aren't finite.
+    pub fn cast<F: Fn(Vi2d) -> bool>(&self, is_solid: F) -> Option<GridHit> {
+        self.walk(is_solid, |_| {})
+    }
+
+    /// Same as [`GridRay::cast`] but every visited cell is pushed into `cells`, starting with the
+    /// cell containing the start and ending with the hit cell
+    pub fn cast_with_cells<F: Fn(Vi2d) -> bool>(
+        &self,
+        is_solid: F,
+        cells: &mut Vec<Vi2d>,
+    ) -> Option<GridHit> {
+        self.walk(is_solid, |cell| cells.push(cell))
+    }
+
+    fn walk<F: Fn(Vi2d) -> bool>(
+        &self,
+        is_solid: F,
+        mut visit: impl FnMut(Vi2d),
+    ) -> Option<GridHit> {
+        let len = self.dir.mag_f64();
+        // Nothing would stop the walk, `distance > NaN` is always false
+        if !len.is_finite()
+            || len <= 0.0
+            || !self.max_distance.is_finite()
+            || self.max_distance < 0.0
+            || !self.start.x.is_finite()
+            || !self.start.y.is_finite()
+            || self.start.x.abs() >= MAX_CELL as f32
+            || self.start.y.abs() >= MAX_CELL as f32
+        {
+            return None;
+        }
+        let dir = self.dir / len;
+        let mut cell = self.start.floor().cast_i32();
+        visit(cell);
+
+        // Distance along the ray to go through a whole cell on each axis
+        let delta = Vf2d {
+            x: dir.x.abs().recip(),
+            y: dir.y.abs().recip(),
+        };
+        let step = Vi2d {
+            x: if dir.x < 0.0 { -1 } else { 1 },
+            y: if dir.y < 0.0 { -1 } else { 1 },
+        };
+        // Distance along the ray to the next vertical and horizontal grid lines
+        let mut next = Vf2d {
+            x: if dir.x < 0.0 {
+                self.start.x - cell.x as f32
+            } else {
+                cell.x as f32 + 1.0 - self.start.x
+            } * delta.x,
+            y: if dir.y < 0.0 {
+                self.start.y - cell.y as f32
+            } else {
+                cell.y as f32 + 1.0 - self.start.y
+            } * delta.y,
+        };
+
+        loop {
+            let (distance, side) = if next.x < next.y {
+                let d = next.x;
+                cell.x += step.x;
+                next.x += delta.x;
+                (d, if step.x > 0 { Side::West } else { Side::East })
+            } else {
+                let d = next.y;
+                cell.y += step.y;
+                next.y += delta.y;
+                (d, if step.y > 0 { Side::North } else { Side::South })
+            };
+            // Also stops before the cells overflow
+            if distance > self.max_distance || cell.x.abs() > MAX_CELL || cell.y.abs() > MAX_CELL {
+                return None;
+            }
+            visit(cell);
+            if is_solid(cell) {
+                return Some(GridHit {
+                    cell,
+                    point: self.start + dir * distance,
+                    side,
+                    distance,
+                    perp_distance: distance / len,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty(_: Vi2d) -> bool {
+        false
+    }
+
+    #[test]
+    fn hits_the_first_solid_cell() {
+        let wall = |cell: Vi2d| cell.x == 5;
+        let hit = GridRay::new(Vf2d::new(0.5, 0.5), Vf2d::new(2.0, 0.0), 100.0)
+            .cast(wall)
+            .unwrap();
+        assert_eq!(hit.cell, Vi2d::new(5, 0));
+        assert_eq!(hit.side, Side::West);
+        assert!((hit.distance - 4.5).abs() < 1e-6);
+        assert!((hit.perp_distance - 2.25).abs() < 1e-6);
+        assert!(GridRay::new(Vf2d::new(0.5, 0.5), Vf2d::new(1.0, 0.0), 4.0)
+            .cast(wall)
+            .is_none());
+    }
+
+    #[test]
+    fn non_finite_rays() {
+        let start = Vf2d::new(0.5, 0.5);
+        let dir = Vf2d::new(1.0, 0.3);
+        for max_distance in [f32::INFINITY, f32::NAN, -1.0] {
+            assert!(GridRay::new(start, dir, max_distance).cast(empty).is_none());
+        }
+        assert!(GridRay::new(start, Vf2d::new(f32::NAN, 0.3), 10.0)
+            .cast(empty)
+            .is_none());
+        assert!(GridRay::new(start, Vf2d::new(0.0, 0.0), 10.0)
+            .cast(empty)
+            .is_none());
+        assert!(GridRay::new(Vf2d::new(f32::INFINITY, 0.5), dir, 10.0)
+            .cast(empty)
+            .is_none());
+    }
+
+    #[test]
+    fn no_limit_over_an_empty_grid() {
+        for dir in [
+            Vf2d::new(1.0, 0.0),
+            Vf2d::new(-1.0, 0.0),
+            Vf2d::new(0.0, -1.0),
+            Vf2d::new(1.0, 0.3),
+        ] {
+            assert!(GridRay::new(Vf2d::new(0.5, 0.5), dir, f32::MAX)
+                .cast(empty)
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn start_far_away() {
+        let far = GridRay::new(Vf2d::new(2.0e9, 0.5), Vf2d::new(1.0, 0.0), f32::MAX);
+        assert!(far.cast(|_| true).is_none());
+        // Close to the limit the walk stops before overflowing
+        let mut cells = Vec::new();
+        let edge = GridRay::new(Vf2d::new(16_777_200.0, 0.5), Vf2d::new(1.0, 0.0), f32::MAX);
+        assert!(edge.cast_with_cells(empty, &mut cells).is_none());
+        assert_eq!(cells.last(), Some(&Vi2d::new(MAX_CELL, 0)));
+    }
+}
@@ -0,0 +1,192 @@
+//! Axis aligned rectangles
+//!
+//! A [`Rect`] is a position (the top left corner) and a size. The right and bottom edges are
+//! excluded, so a rectangle at `(0, 0)` of size `(2, 2)` contains the cells `(0, 0)` to `(1, 1)`.
+
+use std::ops::{Add, Div, Sub};
+
+use crate::vector2::Vec2d;
+
+/// An axis aligned rectangle
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rect<T> {
+    /// Top left corner
+    pub pos: Vec2d<T>,
+    /// Width and height
+    pub size: Vec2d<T>,
+}
+
+/// Rect<i32>
+pub type Recti = Rect<i32>;
+/// Rect<u32>
+pub type Rectu = Rect<u32>;
+/// Rect<f32>
+pub type Rectf = Rect<f32>;
+
+impl<T> Rect<T> {
+    /// Create a new [`Rect`] from its top left corner and its size
+    #[inline]
+    pub const fn new(pos: Vec2d<T>, size: Vec2d<T>) -> Self {
+        Self { pos, size }
+    }
+}
+
+impl<T: Copy> Rect<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Div<Output = T> + PartialOrd + Default + From<u8>,
+{
+    /// Create a [`Rect`] from its top left corner (included) and its bottom right corner
+    /// (excluded)
+    ///
+    /// The corners are swapped if needed
+    pub fn from_corners(a: Vec2d<T>, b: Vec2d<T>) -> Self {
+        let pos = a.min(&b);
+        Self {
+            pos,
+            size: a.max(&b) - pos,
+        }
+    }
+
+    /// Return the top left corner (included)
+    #[inline]
+    pub fn min(&self) -> Vec2d<T> {
+        self.pos
+    }
+
+    /// Return the bottom right corner (excluded)
+    #[inline]
+    pub fn max(&self) -> Vec2d<T> {
+        self.pos + self.size
+    }
+
+    /// Return the width of the rectangle
+    #[inline]
+    pub fn width(&self) -> T {
+        self.size.x
+    }
+
+    /// Return the height of the rectangle
+    #[inline]
+    pub fn height(&self) -> T {
+        self.size.y
+    }
+
+    /// Return the center of the rectangle
+    pub fn center(&self) -> Vec2d<T> {
+        self.pos + self.size / T::from(2)
+    }
+
+    /// Return true if the rectangle doesn't contain anything
+    pub fn is_empty(&self) -> bool {
+        self.size.x <= T::default() || self.size.y <= T::default()
+    }
+
+    /// Return true if the point is inside of the rectangle
+    pub fn contains(&self, point: impl Into<Vec2d<T>>) -> bool {
+        let p = point.into();
+        let max = self.max();
+        p.x >= self.pos.x && p.y >= self.pos.y && p.x < max.x && p.y < max.y
+    }
+
+    /// Return true if `other` is fully inside of the rectangle
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        let (max, other_max) = (self.max(), other.max());
+        other.pos.x >= self.pos.x
+            && other.pos.y >= self.pos.y
+            && other_max.x <= max.x
+            && other_max.y <= max.y
+    }
+
+    /// Return true if both rectangles share some space, empty rectangles never intersect
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Return the space shared by both rectangles, if there is any
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let pos = self.pos.max(&other.pos);
+        let max = self.max().min(&other.max());
+        if max.x <= pos.x || max.y <= pos.y {
+            return None;
+        }
+        Some(Self {
+            pos,
+            size: max - pos,
+        })
+    }
+
+    /// Return the smallest rectangle containing both rectangles, empty rectangles are ignored
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+        Self::from_corners(self.pos.min(&other.pos), self.max().max(&other.max()))
+    }
+
+    /// Return the rectangle moved by `offset`
+    #[must_use]
+    pub fn translate(&self, offset: impl Into<Vec2d<T>>) -> Self {
+        Self {
+            pos: self.pos + offset.into(),
+            size: self.size,
+        }
+    }
+}
+
+macro_rules! cells_impl {
+    ($t:ty) => {
+        impl Rect<$t> {
+            /// Iterate over every cell (pixel) of the rectangle, row by row
+            pub fn cells(&self) -> impl Iterator<Item = Vec2d<$t>> {
+                let Rect { pos, size } = *self;
+                (pos.y..pos.y + size.y)
+                    .flat_map(move |y| (pos.x..pos.x + size.x).map(move |x| Vec2d { x, y }))
+            }
+        }
+    };
+}
+
+cells_impl!(i32);
+cells_impl!(u32);
+
+macro_rules! inflate_impl {
+    ($t:ty, $sub:path) => {
+        impl Rect<$t> {
+            /// Grow the rectangle by `amount` on every side, a negative amount shrinks it
+            ///
+            /// The size never goes below 0, and for unsigned types the position neither
+            #[must_use]
+            pub fn inflate(&self, amount: $t) -> Self {
+                let pos = Vec2d {
+                    x: $sub(self.pos.x, amount),
+                    y: $sub(self.pos.y, amount),
+                };
+                let max = self.max() + Vec2d { x: amount, y: amount };
+                let zero = Vec2d {
+                    x: <$t>::default(),
+                    y: <$t>::default(),
+                };
+                Self {
+                    pos,
+                    size: (max - pos).max(&zero),
+                }
+            }
+        }
+    };
+}
+
+inflate_impl!(i32, Sub::sub);
+inflate_impl!(u32, u32::saturating_sub);
+inflate_impl!(f32, Sub::sub);
+inflate_impl!(f64, Sub::sub);
+
+impl<T> From<(Vec2d<T>, Vec2d<T>)> for Rect<T> {
+    /// Create a [`Rect`] from a `(pos, size)` pair
+    fn from((pos, size): (Vec2d<T>, Vec2d<T>)) -> Self {
+        Self { pos, size }
+    }
+}
@@ -0,0 +1,285 @@
+#![allow(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss
+)]
+//! Texture sampling with selectable filtering and addressing
+//!
+//! [`Sprite::get_sample`] only does a nearest lookup on the fractional part of the coordinates.
+//! A [`Sampler`] lets you choose how texels are filtered and what happens outside of `[0; 1)`.
+
+use crate::graphics::{Color, DrawSpriteTrait, Sprite};
+use crate::vector2::Vu2d;
+
+/// How the texels are combined when sampling
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Take the closest texel
+    Nearest,
+    /// Linearly interpolate between the 4 closest texels
+    Bilinear,
+}
+
+/// What to do when a sample falls outside of the sprite
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AddressMode {
+    /// Tile the sprite infinitely
+    Repeat,
+    /// Tile the sprite infinitely, flipping it every other tile
+    MirrorRepeat,
+    /// Use the color of the closest edge texel
+    ClampToEdge,
+    /// Use the given color outside of the sprite
+    ClampToBorder(Color),
+}
+
+/// Describe how to read a sprite at normalized coordinates
+///
+/// The coordinates are normalized, `(0.0, 0.0)` being the top left corner and `(1.0, 1.0)` the
+/// bottom right corner of the sprite.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Sampler {
+    /// The filter used
+    pub filter: FilterMode,
+    /// The [`AddressMode`] on the horizontal axis
+    pub address_u: AddressMode,
+    /// The [`AddressMode`] on the vertical axis
+    pub address_v: AddressMode,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::new(FilterMode::Nearest, AddressMode::Repeat)
+    }
+}
+
+impl AddressMode {
+    /// Map a texel index onto the `[0; size)` range, or `None` if the border color must be used
+    #[inline]
+    fn apply(self, i: i64, size: u32) -> Option<u32> {
+        let size = i64::from(size);
+        match self {
+            AddressMode::Repeat => Some(i.rem_euclid(size) as u32),
+            AddressMode::MirrorRepeat => {
+                let m = i.rem_euclid(2 * size);
+                Some(if m >= size { 2 * size - 1 - m } else { m } as u32)
+            }
+            AddressMode::ClampToEdge => Some(i.clamp(0, size - 1) as u32),
+            AddressMode::ClampToBorder(_) => (0..size).contains(&i).then_some(i as u32),
+        }
+    }
+
+    #[inline]
+    fn border(self) -> Color {
+        match self {
+            AddressMode::ClampToBorder(col) => col,
+            _ => Color::BLANK,
+        }
+    }
+}
+
+impl Sampler {
+    /// Create a [`Sampler`] using the same [`AddressMode`] on both axis
+    #[must_use]
+    pub const fn new(filter: FilterMode, address: AddressMode) -> Self {
+        Self {
+            filter,
+            address_u: address,
+            address_v: address,
+        }
+    }
+
+    /// A nearest sampler that tiles the sprite, this is what [`Sprite::get_sample`] does for
+    /// coordinates in `[0; 1)`
+    pub const NEAREST_REPEAT: Sampler = Sampler::new(FilterMode::Nearest, AddressMode::Repeat);
+    /// A nearest sampler that clamps to the edges of the sprite
+    pub const NEAREST_CLAMP: Sampler = Sampler::new(FilterMode::Nearest, AddressMode::ClampToEdge);
+    /// A bilinear sampler that tiles the sprite
+    pub const BILINEAR_REPEAT: Sampler = Sampler::new(FilterMode::Bilinear, AddressMode::Repeat);
+    /// A bilinear sampler that clamps to the edges of the sprite
+    pub const BILINEAR_CLAMP: Sampler =
+        Sampler::new(FilterMode::Bilinear, AddressMode::ClampToEdge);
+
+    /// Return the same [`Sampler`] with the given [`FilterMode`]
+    #[must_use]
+    pub const fn with_filter(mut self, filter: FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Return the same [`Sampler`] with the given [`AddressMode`] on both axis
+    #[must_use]
+    pub const fn with_address_mode(mut self, address: AddressMode) -> Self {
+        self.address_u = address;
+        self.address_v = address;
+        self
+    }
+
+    /// Return the same [`Sampler`] with the given [`AddressMode`]s on each axis
+    #[must_use]
+    pub const fn with_address_modes(
+        mut self,
+        address_u: AddressMode,
+        address_v: AddressMode,
+    ) -> Self {
+        self.address_u = address_u;
+        self.address_v = address_v;
+        self
+    }
+
+    /// Fetch a single texel, applying the address modes
+    #[inline]
+    fn texel<S: DrawSpriteTrait + ?Sized>(&self, spr: &S, size: Vu2d, x: i64, y: i64) -> Color {
+        match (
+            self.address_u.apply(x, size.x),
+            self.address_v.apply(y, size.y),
+        ) {
+            // SAFETY: `AddressMode::apply` only returns indices in `[0; size)`
+            (Some(x), Some(y)) => unsafe { spr.get_pixel_unchecked(Vu2d { x, y }) },
+            (None, _) => self.address_u.border(),
+            (_, None) => self.address_v.border(),
+        }
+    }
+
+    /// Sample the target at the given normalized coordinates
+    ///
+    /// An empty target will always return [`Color::BLANK`]. Coordinates that aren't finite are
+    /// accepted: an infinity is far outside of the target and `NaN` is read as `0.0`
+    pub fn sample<S: DrawSpriteTrait + ?Sized>(&self, spr: &S, x: f64, y: f64) -> Color {
+        let size = spr.size();
+        if size.x == 0 || size.y == 0 {
+            return Color::BLANK;
+        }
+        let tx = x * f64::from(size.x);
+        let ty = y * f64::from(size.y);
+        match self.filter {
+            FilterMode::Nearest => self.texel(spr, size, tx.floor() as i64, ty.floor() as i64),
+            FilterMode::Bilinear => {
+                // Texel centers are at half coordinates
+                let tx = tx - 0.5;
+                let ty = ty - 0.5;
+                let (x0, fx) = split_texel(tx);
+                let (y0, fy) = split_texel(ty);
+                // The index saturates for huge coordinates
+                let (x1, y1) = (x0.saturating_add(1), y0.saturating_add(1));
+
+                let c00: [f32; 4] = self.texel(spr, size, x0, y0).into();
+                let c10: [f32; 4] = self.texel(spr, size, x1, y0).into();
+                let c01: [f32; 4] = self.texel(spr, size, x0, y1).into();
+                let c11: [f32; 4] = self.texel(spr, size, x1, y1).into();
+
+                let mut out = [0f32; 4];
+                for (i, o) in out.iter_mut().enumerate() {
+                    let top = c00[i] + (c10[i] - c00[i]) * fx;
+                    let bottom = c01[i] + (c11[i] - c01[i]) * fx;
+                    // Round to the closest byte instead of truncating
+                    *o = top + (bottom - top) * fy + 0.5 / 255.0;
+                }
+                out.into()
+            }
+        }
+    }
+}
+
+/// Split a texel coordinate into the index of the texel and the position inside of it
+#[inline]
+fn split_texel(t: f64) -> (i64, f32) {
+    let floor = t.floor();
+    let fract = t - floor;
+    // The fraction of an infinity or NaN is NaN
+    (
+        floor as i64,
+        if fract.is_finite() { fract as f32 } else { 0.0 },
+    )
+}
+
+impl Sprite {
+    /// Return the [`Color`] of the sprite at the given normalized coordinates using the given
+    /// [`Sampler`]
+    pub fn sample(&self, sampler: &Sampler, x: f64, y: f64) -> Color {
+        sampler.sample(self, x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Color = Color::RED;
+    const B: Color = Color::GREEN;
+    const C: Color = Color::BLUE;
+    const D: Color = Color::WHITE;
+    const BORDER: Color = Color::BLACK;
+
+    /// A 2x2 sprite, `A B` on the top row and `C D` on the bottom one
+    fn sprite() -> Sprite {
+        let mut spr = Sprite::new(2, 2);
+        for (x, y, col) in [(0, 0, A), (1, 0, B), (0, 1, C), (1, 1, D)] {
+            spr.set_pixel(x, y, col);
+        }
+        spr
+    }
+
+    fn modes() -> [AddressMode; 4] {
+        [
+            AddressMode::Repeat,
+            AddressMode::MirrorRepeat,
+            AddressMode::ClampToEdge,
+            AddressMode::ClampToBorder(BORDER),
+        ]
+    }
+
+    #[test]
+    fn nearest() {
+        let spr = sprite();
+        let sampler = Sampler::NEAREST_REPEAT;
+        assert_eq!(spr.sample(&sampler, 0.25, 0.25), A);
+        assert_eq!(spr.sample(&sampler, 0.75, 0.75), D);
+        assert_eq!(spr.sample(&sampler, 1.25, -0.25), C);
+        let mirror = sampler.with_address_mode(AddressMode::MirrorRepeat);
+        assert_eq!(spr.sample(&mirror, 1.25, 0.25), B);
+        assert_eq!(spr.sample(&Sampler::NEAREST_CLAMP, 5.0, -5.0), B);
+        let border = sampler.with_address_mode(AddressMode::ClampToBorder(BORDER));
+        assert_eq!(spr.sample(&border, 1.25, 0.25), BORDER);
+    }
+
+    #[test]
+    fn bilinear() {
+        let mut spr = Sprite::new_with_color(2, 1, Color::BLACK);
+        spr.set_pixel(1, 0, Color::WHITE);
+        let col = spr.sample(&Sampler::BILINEAR_CLAMP, 0.5, 0.5);
+        assert!((127..=128).contains(&col.r), "{col:?}");
+        assert_eq!(
+            spr.sample(&Sampler::BILINEAR_CLAMP, 0.25, 0.5),
+            Color::BLACK
+        );
+        assert_eq!(
+            spr.sample(&Sampler::BILINEAR_CLAMP, 0.75, 0.5),
+            Color::WHITE
+        );
+    }
+
+    #[test]
+    fn non_finite_coordinates() {
+        let spr = sprite();
+        for filter in [FilterMode::Nearest, FilterMode::Bilinear] {
+            for mode in modes() {
+                let sampler = Sampler::new(filter, mode);
+                for x in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN, f64::MAX, 0.25] {
+                    for y in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN, f64::MIN, 0.25] {
+                        let col = spr.sample(&sampler, x, y);
+                        assert!([A, B, C, D, BORDER].contains(&col), "{sampler:?} {x} {y}");
+                    }
+                }
+            }
+            let clamp = Sampler::new(filter, AddressMode::ClampToEdge);
+            assert_eq!(spr.sample(&clamp, f64::INFINITY, f64::INFINITY), D);
+            assert_eq!(spr.sample(&clamp, f64::NEG_INFINITY, f64::INFINITY), C);
+            assert_eq!(spr.sample(&clamp, f64::NAN, f64::NAN), A);
+            let border = Sampler::new(filter, AddressMode::ClampToBorder(BORDER));
+            assert_eq!(spr.sample(&border, f64::INFINITY, 0.25), BORDER);
+            assert_eq!(spr.sample(&border, 0.25, f64::NEG_INFINITY), BORDER);
+        }
+    }
+}
@@ -0,0 +1,230 @@
+#![allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+//! Per-pixel "CPU fragment shaders"
+//!
+//! Instead of calling [`SmartDrawingTrait::draw`](crate::traits::SmartDrawingTrait::draw) once
+//! per pixel, a closure is run on every pixel of the target and its return value is written
+//! back. The parallel variants split the target into disjoint bands and run them on multiple
+//! threads.
+
+use crate::graphics::{Color, DrawSpriteTrait, DrawingSprite, Sprite, SpriteMutRef};
+use crate::sampler::Sampler;
+use crate::vector2::{Vi2d, Vu2d};
+
+/// A read only view of the pixels of a [`Sprite`], given to the shaders that read from a source
+#[derive(Debug, Clone, Copy)]
+pub struct PixelView<'a> {
+    data: &'a [u8],
+    size: Vu2d,
+}
+
+impl<'a> PixelView<'a> {
+    fn new(data: &'a [u8], size: Vu2d) -> Self {
+        Self { data, size }
+    }
+
+    /// Return the size of the source
+    #[must_use]
+    pub fn size(&self) -> Vu2d {
+        self.size
+    }
+
+    /// Return the [`Color`] of the pixel at the given coordinates, if it exist
+    #[inline]
+    pub fn get_pixel(&self, pos: impl Into<Vi2d>) -> Option<Color> {
+        let pos = pos.into();
+        if pos.x < 0 || pos.y < 0 || pos.x as u32 >= self.size.x || pos.y as u32 >= self.size.y {
+            return None;
+        }
+        // SAFETY: We just checked the bounds
+        Some(unsafe { self.get_pixel_unchecked(pos.cast_u32()) })
+    }
+
+    /// Return the [`Color`] of the pixel at the given coordinates, coordinates outside of the
+    /// source are clamped to the closest edge
+    #[inline]
+    pub fn get_pixel_clamped(&self, pos: impl Into<Vi2d>) -> Color {
+        if self.size.x == 0 || self.size.y == 0 {
+            return Color::BLANK;
+        }
+        let pos = pos.into();
+        let pos = Vu2d {
+            x: (pos.x.max(0) as u32).min(self.size.x - 1),
+            y: (pos.y.max(0) as u32).min(self.size.y - 1),
+        };
+        // SAFETY: The position was clamped inside the bounds
+        unsafe { self.get_pixel_unchecked(pos) }
+    }
+
+    /// Sample the source at the given normalized coordinates
+    #[must_use]
+    pub fn sample(&self, sampler: &Sampler, x: f64, y: f64) -> Color {
+        sampler.sample(self, x, y)
+    }
+}
+
+impl DrawSpriteTrait for PixelView<'_> {
+    fn get_pixel(&self, pos: Vi2d) -> Option<Color> {
+        PixelView::get_pixel(self, pos)
+    }
+
+    /// A [`PixelView`] is read only, this does nothing
+    fn set_pixel(&mut self, _pos: Vi2d, _col: Color) {}
+
+    fn size(&self) -> Vu2d {
+        self.size
+    }
+
+    unsafe fn get_pixel_unchecked(&self, pos: Vu2d) -> Color {
+        let i = (pos.y * self.size.x + pos.x) as usize * 4;
+        [
+            *self.data.get_unchecked(i),
+            *self.data.get_unchecked(i + 1),
+            *self.data.get_unchecked(i + 2),
+            *self.data.get_unchecked(i + 3),
+        ]
+        .into()
+    }
+
+    /// A [`PixelView`] is read only, this does nothing
+    unsafe fn set_pixel_unchecked(&mut self, _pos: Vu2d, _col: Color) {}
+}
+
+/// Run closures on every pixel of the target, one pixel after the other
+///
+/// The value returned by the closure is written as is, without looking at the
+/// [`PixelMode`](crate::graphics::PixelMode)
+pub trait PixelShaderTrait: DrawSpriteTrait {
+    /// Call `f` with the position and the current [`Color`] of every pixel, and replace the pixel
+    /// by the returned [`Color`]
+    fn for_each_pixel<F: FnMut(Vu2d, Color) -> Color>(&mut self, mut f: F) {
+        let size = self.size();
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let pos = Vu2d { x, y };
+                // SAFETY: pos is inside the target
+                unsafe {
+                    let col = f(pos, self.get_pixel_unchecked(pos));
+                    self.set_pixel_unchecked(pos, col);
+                }
+            }
+        }
+    }
+
+    /// Call `f` for every pixel of the target with its position and a view of the `source`
+    /// sprite, and replace the pixel by the returned [`Color`]
+    ///
+    /// The source and the target don't need to have the same size. The target can be a subsprite
+    /// of the source, the view then shows the source as it was before the call
+    fn shade_from<F: FnMut(Vu2d, &PixelView<'_>) -> Color>(&mut self, source: &Sprite, mut f: F) {
+        // The target may be a subsprite of the source, so the source is copied and its lock
+        // released before writing to the target
+        let pixels = source.get_read_lock().0.to_vec();
+        let view = PixelView::new(&pixels, *source.size());
+        let size = self.size();
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let pos = Vu2d { x, y };
+                // SAFETY: pos is inside the target
+                unsafe { self.set_pixel_unchecked(pos, f(pos, &view)) };
+            }
+        }
+    }
+}
+
+impl<T: DrawSpriteTrait + ?Sized> PixelShaderTrait for T {}
+
+/// Run closures on every pixel of the target using multiple threads
+///
+/// The target is split into horizontal bands, one per available thread. On `wasm32` the bands
+/// are processed one after the other.
+pub trait ParallelShaderTrait {
+    /// Return the [`Sprite`] that will be split between the threads
+    fn shader_target(&mut self) -> &Sprite;
+
+    /// Parallel version of [`PixelShaderTrait::for_each_pixel`]
+    ///
+    /// # Panics
+    ///
+    /// If a subsprite of the target already exists
+    fn par_for_each_pixel<F: Fn(Vu2d, Color) -> Color + Sync>(&mut self, f: F) {
+        run_in_bands(self.shader_target(), |mut band| {
+            let offset = band.pos();
+            let size = DrawSpriteTrait::size(&band);
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    let pos = Vu2d { x, y };
+                    // SAFETY: pos is inside the band
+                    unsafe {
+                        let col = f(pos + offset, band.get_pixel_unchecked(pos));
+                        band.set_pixel_unchecked(pos, col);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Parallel version of [`PixelShaderTrait::shade_from`]
+    ///
+    /// # Panics
+    ///
+    /// If a subsprite of the target already exists
+    fn par_shade_from<F: Fn(Vu2d, &PixelView<'_>) -> Color + Sync>(
+        &mut self,
+        source: &Sprite,
+        f: F,
+    ) {
+        let (raw, lock) = source.get_read_lock();
+        let view = PixelView::new(raw, *source.size());
+        run_in_bands(self.shader_target(), |mut band| {
+            let offset = band.pos();
+            let size = DrawSpriteTrait::size(&band);
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    let pos = Vu2d { x, y };
+                    // SAFETY: pos is inside the band
+                    unsafe { band.set_pixel_unchecked(pos, f(pos + offset, &view)) };
+                }
+            }
+        });
+        drop(lock);
+    }
+}
+
+impl ParallelShaderTrait for Sprite {
+    fn shader_target(&mut self) -> &Sprite {
+        self
+    }
+}
+
+impl ParallelShaderTrait for DrawingSprite<Sprite> {
+    fn shader_target(&mut self) -> &Sprite {
+        self.get_ref()
+    }
+}
+
+/// Split the sprite into one band per thread and run `job` on each of them
+fn run_in_bands<F: Fn(SpriteMutRef<'_>) + Sync>(spr: &Sprite, job: F) {
+    #[cfg(not(target_arch = "wasm32"))]
+    let threads = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+    #[cfg(target_arch = "wasm32")]
+    let threads = 1;
+
+    let band_height = spr
+        .height()
+        .div_ceil(u32::try_from(threads).unwrap_or(u32::MAX))
+        .max(1);
+    let bands = spr
+        .split_rows(band_height)
+        .expect("The target of a shader can't have any subsprite");
+
+    if bands.len() <= 1 {
+        bands.into_iter().for_each(job);
+        return;
+    }
+    let job = &job;
+    std::thread::scope(|scope| {
+        for band in bands {
+            scope.spawn(move || job(band));
+        }
+    });
+}
@@ -1,4 +1,6 @@
 use super::graphics::{Color, PixelMode, Sprite};
+use super::palette::{IndexedSprite, Palette};
+use super::rect::Recti;
 use super::vector2::{Vi2d, Vu2d};
 
 use crate::graphics::DrawSpriteTrait;
@@ -135,6 +137,12 @@ pub trait DottedShapeTrait: SmartDrawingTrait {
         self.draw_line_dotted((x, y + h), (x, y), col, pattern);
     }
 
+    /// Same as [`DottedShapeTrait::draw_rect_dotted`] with a [`Rect`](crate::rect::Rect)
+    fn draw_rect_area_dotted(&mut self, area: impl Into<Recti>, col: Color, pattern: u32) {
+        let area = area.into();
+        self.draw_rect_dotted(area.pos, area.size, col, pattern);
+    }
+
     /// Draw the edges of a triangle between the three points
     /// This is the dotted form
     fn draw_triangle_dotted<P: Into<Vi2d>>(
@@ -363,6 +371,18 @@ pub trait ShapesTrait: SmartDrawingTrait {
         }
     }
 
+    /// Same as [`ShapesTrait::draw_rect`] with a [`Rect`](crate::rect::Rect)
+    fn draw_rect_area(&mut self, area: impl Into<Recti>, col: Color) {
+        let area = area.into();
+        self.draw_rect(area.pos, area.size, col);
+    }
+
+    /// Same as [`ShapesTrait::fill_rect`] with a [`Rect`](crate::rect::Rect)
+    fn fill_rect_area(&mut self, area: impl Into<Recti>, col: Color) {
+        let area = area.into();
+        self.fill_rect(area.pos, area.size, col);
+    }
+
     /// Draw a circle with center `(x, y)` and raduis `r`
     fn draw_circle<P: Into<Vi2d>>(&mut self, pos: P, r: u32, col: Color) {
         let Vi2d { x, y } = pos.into();
@@ -654,6 +674,64 @@ pub trait SpriteTrait: SmartDrawingTrait {
             }
         }
     }
+
+    /// Same as [`SpriteTrait::draw_partial_sprite`] with the chunk of the sprite given as a
+    /// [`Rect`](crate::rect::Rect)
+    fn draw_partial_sprite_area<P: Into<Vi2d>>(
+        &mut self,
+        coords: P,
+        sprite: &Sprite,
+        source: impl Into<Recti>,
+        scale: u32,
+        flip: (bool, bool),
+    ) {
+        let source = source.into();
+        self.draw_partial_sprite(coords.into(), sprite, source.pos, source.size, scale, flip);
+    }
+
+    /// Draw an [`IndexedSprite`] with the top left corner at `(x, y)` using its own palette
+    /// `flip` and `scale` is the same as [`SpriteTrait::draw_sprite()`]
+    fn draw_indexed_sprite<P: Into<Vi2d>>(
+        &mut self,
+        pos: P,
+        scale: u32,
+        sprite: &IndexedSprite,
+        flip: (bool, bool),
+    ) {
+        self.draw_indexed_sprite_with_palette(pos, scale, sprite, sprite.palette(), flip);
+    }
+
+    /// Draw an [`IndexedSprite`] with the top left corner at `(x, y)` using the given palette
+    /// instead of the sprite's one, this is useful for palette swaps
+    /// `flip` and `scale` is the same as [`SpriteTrait::draw_sprite()`]
+    fn draw_indexed_sprite_with_palette<P: Into<Vi2d>>(
+        &mut self,
+        pos: P,
+        scale: u32,
+        sprite: &IndexedSprite,
+        palette: &Palette,
+        flip: (bool, bool),
+    ) {
+        #![allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+        let Vi2d { x, y } = pos.into();
+        let scale = scale.max(1) as i32;
+        let (w, h) = (sprite.width() as i32, sprite.height() as i32);
+        for i in 0..w {
+            let fx = if flip.0 { w - 1 - i } else { i };
+            for j in 0..h {
+                let fy = if flip.1 { h - 1 - j } else { j };
+                let Some(index) = sprite.get_index(fx as u32, fy as u32) else {
+                    continue;
+                };
+                let col = palette.get(index);
+                for is in 0..scale {
+                    for js in 0..scale {
+                        self.draw((x + i * scale + is, y + j * scale + js), col);
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl_trait!(SpriteTrait);
@@ -16,6 +16,12 @@ pub type Vi2d = Vec2d<i32>;
 pub type Vf2d = Vec2d<f32>;
 
 impl<T> Vec2d<T> {
+    /// Create a new Vec2d
+    #[inline]
+    pub const fn new(x: T, y: T) -> Self {
+        Vec2d { x, y }
+    }
+
     /// Cast the Vec2d to an other Vec2d with a differant inner type
     #[inline]
     pub fn cast<U: From<T>>(self) -> Vec2d<U> {
@@ -73,52 +79,279 @@ where
             y: self.x,
         }
     }
+}
 
+impl<T: Copy> Vec2d<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
     /// Perform the dot product on the Vec2ds
     pub fn dot(&self, rhs: &Self) -> T {
-        self.x + rhs.x + self.y + rhs.y
+        self.x * rhs.x + self.y * rhs.y
     }
 
-    /// Perform the cross product on the Vec2ds
+    /// Perform the cross product on the Vec2ds (the z component of the 3d cross product)
     pub fn cross(&self, rhs: &Self) -> T {
-        self.x + rhs.x - self.y + rhs.y
+        self.x * rhs.y - self.y * rhs.x
+    }
+}
+
+impl<T: Copy + PartialOrd> Vec2d<T> {
+    /// Return the component-wise minimum of the Vec2ds
+    #[must_use]
+    pub fn min(&self, rhs: &Self) -> Self {
+        Vec2d {
+            x: if rhs.x < self.x { rhs.x } else { self.x },
+            y: if rhs.y < self.y { rhs.y } else { self.y },
+        }
+    }
+
+    /// Return the component-wise maximum of the Vec2ds
+    #[must_use]
+    pub fn max(&self, rhs: &Self) -> Self {
+        Vec2d {
+            x: if rhs.x > self.x { rhs.x } else { self.x },
+            y: if rhs.y > self.y { rhs.y } else { self.y },
+        }
+    }
+
+    /// Clamp every component between the components of `min` and `max`
+    #[must_use]
+    pub fn clamp(&self, min: &Self, max: &Self) -> Self {
+        self.max(min).min(max)
+    }
+}
+
+macro_rules! float_impl {
+    ($t:ty) => {
+        impl Vec2d<$t> {
+            /// Linearly interpolate between the Vec2ds, `t = 0.0` returning `self`
+            #[must_use]
+            pub fn lerp(&self, rhs: &Self, t: $t) -> Self {
+                *self + (*rhs - *self) * t
+            }
+
+            /// Return the angle (in radians) between the Vec2d and the x axis, in `[-pi; pi]`
+            #[must_use]
+            pub fn angle(&self) -> $t {
+                self.y.atan2(self.x)
+            }
+
+            /// Return the signed angle (in radians) to go from `self` to `rhs`
+            #[must_use]
+            pub fn angle_to(&self, rhs: &Self) -> $t {
+                self.cross(rhs).atan2(self.dot(rhs))
+            }
+
+            /// Create a unit Vec2d with the given angle (in radians)
+            #[must_use]
+            pub fn from_angle(angle: $t) -> Self {
+                let (sin, cos) = angle.sin_cos();
+                Vec2d { x: cos, y: sin }
+            }
+
+            /// Rotate the Vec2d by the given angle (in radians)
+            #[must_use]
+            pub fn rotate(&self, angle: $t) -> Self {
+                let (sin, cos) = angle.sin_cos();
+                Vec2d {
+                    x: self.x * cos - self.y * sin,
+                    y: self.x * sin + self.y * cos,
+                }
+            }
+
+            /// Reflect the Vec2d on a surface with the given normal, the normal must be normalized
+            #[must_use]
+            pub fn reflect(&self, normal: &Self) -> Self {
+                *self - *normal * (2.0 * self.dot(normal))
+            }
+
+            /// Return the distance between the two points
+            #[must_use]
+            pub fn distance(&self, rhs: &Self) -> $t {
+                self.distance2(rhs).sqrt()
+            }
+
+            /// Return the squared distance between the two points
+            #[must_use]
+            pub fn distance2(&self, rhs: &Self) -> $t {
+                let d = *self - *rhs;
+                d.x * d.x + d.y * d.y
+            }
+
+            /// Round every component down
+            #[must_use]
+            pub fn floor(&self) -> Self {
+                Vec2d {
+                    x: self.x.floor(),
+                    y: self.y.floor(),
+                }
+            }
+
+            /// Round every component up
+            #[must_use]
+            pub fn ceil(&self) -> Self {
+                Vec2d {
+                    x: self.x.ceil(),
+                    y: self.y.ceil(),
+                }
+            }
+
+            /// Round every component to the nearest integer
+            #[must_use]
+            pub fn round(&self) -> Self {
+                Vec2d {
+                    x: self.x.round(),
+                    y: self.y.round(),
+                }
+            }
+
+            /// Return the absolute value of every component
+            #[must_use]
+            pub fn abs(&self) -> Self {
+                Vec2d {
+                    x: self.x.abs(),
+                    y: self.y.abs(),
+                }
+            }
+        }
+
+        impl Vec3d<$t> {
+            /// Return the magnitude of the Vec3d
+            #[must_use]
+            pub fn mag(&self) -> $t {
+                self.mag2().sqrt()
+            }
+
+            /// Return the magnitude of the Vec3d without doing the square root
+            #[must_use]
+            pub fn mag2(&self) -> $t {
+                self.dot(self)
+            }
+
+            /// Return a normalized version of the Vec3d
+            #[must_use]
+            pub fn norm(&self) -> Self {
+                *self * self.mag().recip()
+            }
+
+            /// Linearly interpolate between the Vec3ds, `t = 0.0` returning `self`
+            #[must_use]
+            pub fn lerp(&self, rhs: &Self, t: $t) -> Self {
+                *self + (*rhs - *self) * t
+            }
+
+            /// Return the distance between the two points
+            #[must_use]
+            pub fn distance(&self, rhs: &Self) -> $t {
+                (*self - *rhs).mag()
+            }
+        }
+    };
+}
+
+float_impl!(f32);
+float_impl!(f64);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// A 3d vector
+pub struct Vec3d<T> {
+    /// x
+    pub x: T,
+    /// y
+    pub y: T,
+    /// z
+    pub z: T,
+}
+
+/// Vec3d<f32>
+pub type Vf3d = Vec3d<f32>;
+/// Vec3d<i32>
+pub type Vi3d = Vec3d<i32>;
+
+impl<T> Vec3d<T> {
+    /// Create a new Vec3d
+    #[inline]
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Vec3d { x, y, z }
+    }
+}
+
+impl<T: Copy> Vec3d<T>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    /// Perform the dot product on the Vec3ds
+    pub fn dot(&self, rhs: &Self) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    /// Perform the cross product on the Vec3ds
+    #[must_use]
+    pub fn cross(&self, rhs: &Self) -> Self {
+        Vec3d {
+            x: self.y * rhs.z - self.z * rhs.y,
+            y: self.z * rhs.x - self.x * rhs.z,
+            z: self.x * rhs.y - self.y * rhs.x,
+        }
     }
 }
 
 macro_rules! operator {
-    ($trait:tt, $operator:tt, $func_name:ident) => {
-        impl<T:Copy> $trait for Vec2d<T> where T: $trait<Output = T>, {
+    ($vec:ident { $($f:ident),+ }, $trait:tt, $operator:tt, $func_name:ident) => {
+        impl<T:Copy> $trait for $vec<T> where T: $trait<Output = T>, {
             type Output = Self;
             fn $func_name(self, rhs: Self) -> Self::Output {
-                Vec2d { x: self.x $operator rhs.x, y: self.y $operator rhs.y}
+                $vec { $($f: self.$f $operator rhs.$f),+ }
             }
         }
     };
-    ($trait:tt, $operator:tt, $func_name:ident, $type:ty) => {
-        impl<T:Copy> $trait<$type> for Vec2d<T> where T: $trait<Output = T>, {
+    ($vec:ident { $($f:ident),+ }, $trait:tt, $operator:tt, $func_name:ident, $type:ty) => {
+        impl<T:Copy> $trait<$type> for $vec<T> where T: $trait<Output = T>, {
             type Output = Self;
             fn $func_name(self, rhs: $type) -> Self::Output {
-                Vec2d { x: self.x $operator rhs, y: self.y $operator rhs}
+                $vec { $($f: self.$f $operator rhs),+ }
             }
         }
     };
 }
 macro_rules! operator_assign {
-    ($trait:tt, $operator:tt, $func_name:ident) => {
-        impl<T:Copy> $trait for Vec2d<T> where T: $trait<T>, {
+    ($vec:ident { $($f:ident),+ }, $trait:tt, $operator:tt, $func_name:ident) => {
+        impl<T:Copy> $trait for $vec<T> where T: $trait<T>, {
             fn $func_name(&mut self, rhs: Self){
-                self.x $operator rhs.x; self.y $operator rhs.y;
+                $(self.$f $operator rhs.$f;)+
             }
             }
         };
-    ($trait:tt, $operator:tt, $func_name:ident, $type:ty) => {
-        impl<T:Copy> $trait<$type> for Vec2d<T> where T: $trait<T>, {
+    ($vec:ident { $($f:ident),+ }, $trait:tt, $operator:tt, $func_name:ident, $type:ty) => {
+        impl<T:Copy> $trait<$type> for $vec<T> where T: $trait<T>, {
             fn $func_name(&mut self, rhs: $type){
-                self.x $operator rhs; self.y $operator rhs;
+                $(self.$f $operator rhs;)+
             }
             }
         };
 }
+macro_rules! vector_operators {
+    ($vec:ident { $($f:ident),+ }) => {
+        operator!($vec { $($f),+ }, Add, + , add);
+        operator!($vec { $($f),+ }, Sub, - , sub);
+        operator!($vec { $($f),+ }, Mul, * , mul);
+        operator!($vec { $($f),+ }, Div, / , div);
+        operator!($vec { $($f),+ }, Mul, * , mul, T);
+        operator!($vec { $($f),+ }, Div, / , div, T);
+        operator_assign!($vec { $($f),+ }, AddAssign, += , add_assign);
+        operator_assign!($vec { $($f),+ }, SubAssign, -= , sub_assign);
+        operator_assign!($vec { $($f),+ }, MulAssign, *= , mul_assign, T);
+        operator_assign!($vec { $($f),+ }, DivAssign, /= , div_assign, T);
+
+        impl<T: Copy> Neg for $vec<T> where T: Neg<Output = T>, {
+            type Output = Self;
+            fn neg(self) -> Self::Output {
+                $vec { $($f: -self.$f),+ }
+            }
+        }
+    };
+}
 
 macro_rules! cast {
     ($from:ty, $to:ty) => {
@@ -135,16 +368,8 @@ macro_rules! cast {
     };
 }
 
-operator!(Add, + , add);
-operator!(Sub, - , sub);
-operator!(Mul, * , mul);
-operator!(Div, / , div);
-operator!(Mul, * , mul, T);
-operator!(Div, / , div, T);
-operator_assign!(AddAssign, += , add_assign);
-operator_assign!(SubAssign, -= , sub_assign);
-operator_assign!(MulAssign, *= , mul_assign, T);
-operator_assign!(DivAssign, /= , div_assign, T);
+vector_operators!(Vec2d { x, y });
+vector_operators!(Vec3d { x, y, z });
 
 cast!(u32, f32);
 cast!(i32, f32);
@@ -165,3 +390,282 @@ impl<T: Copy> From<[T; 2]> for Vec2d<T> {
         Vec2d { x: t[0], y: t[1] }
     }
 }
+
+impl From<Vu2d> for Vf2d {
+    fn from(v: Vu2d) -> Self {
+        v.cast_f32()
+    }
+}
+impl From<Vi2d> for Vf2d {
+    fn from(v: Vi2d) -> Self {
+        v.cast_f32()
+    }
+}
+impl TryFrom<Vi2d> for Vu2d {
+    type Error = std::num::TryFromIntError;
+    /// Fails if a component is negative
+    fn try_from(v: Vi2d) -> Result<Self, Self::Error> {
+        Ok(Vec2d {
+            x: v.x.try_into()?,
+            y: v.y.try_into()?,
+        })
+    }
+}
+impl TryFrom<Vu2d> for Vi2d {
+    type Error = std::num::TryFromIntError;
+    /// Fails if a component is greater than `i32::MAX`
+    fn try_from(v: Vu2d) -> Result<Self, Self::Error> {
+        Ok(Vec2d {
+            x: v.x.try_into()?,
+            y: v.y.try_into()?,
+        })
+    }
+}
+
+impl<T: Copy> From<(T, T, T)> for Vec3d<T> {
+    fn from(t: (T, T, T)) -> Self {
+        Vec3d {
+            x: t.0,
+            y: t.1,
+            z: t.2,
+        }
+    }
+}
+impl<T: Copy> From<[T; 3]> for Vec3d<T> {
+    fn from(t: [T; 3]) -> Self {
+        Vec3d {
+            x: t[0],
+            y: t[1],
+            z: t[2],
+        }
+    }
+}
+
+/// A 3x3 matrix, used as a 2d affine transform
+///
+/// The matrix is stored row by row, and points are column vectors: `m * p`. A composition
+/// `a * b` applies `b` first and then `a`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Mat3 {
+    /// Rows of the matrix
+    pub m: [[f32; 3]; 3],
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mat3 {
+    /// The identity matrix, does nothing
+    pub const IDENTITY: Mat3 = Mat3 {
+        m: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    };
+
+    /// Create a matrix from its rows
+    #[must_use]
+    pub const fn new(m: [[f32; 3]; 3]) -> Self {
+        Self { m }
+    }
+
+    /// A translation by `offset`
+    #[must_use]
+    pub const fn translation(offset: Vf2d) -> Self {
+        Self {
+            m: [[1.0, 0.0, offset.x], [0.0, 1.0, offset.y], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    /// A rotation around the origin by `angle` (in radians)
+    #[must_use]
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            m: [[cos, -sin, 0.0], [sin, cos, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    /// A scale from the origin
+    #[must_use]
+    pub const fn scale(scale: Vf2d) -> Self {
+        Self {
+            m: [[scale.x, 0.0, 0.0], [0.0, scale.y, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    /// A shear, `x' = x + shear.x * y` and `y' = y + shear.y * x`
+    #[must_use]
+    pub const fn shear(shear: Vf2d) -> Self {
+        Self {
+            m: [[1.0, shear.x, 0.0], [shear.y, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    /// Apply `self` and then `next`, this is `next * self`
+    #[must_use]
+    pub fn then(self, next: Mat3) -> Self {
+        next * self
+    }
+
+    /// Return the transposed matrix
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        let m = &self.m;
+        Self {
+            m: [
+                [m[0][0], m[1][0], m[2][0]],
+                [m[0][1], m[1][1], m[2][1]],
+                [m[0][2], m[1][2], m[2][2]],
+            ],
+        }
+    }
+
+    /// Return the determinant of the matrix
+    #[must_use]
+    pub fn determinant(&self) -> f32 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// Return the inverse of the matrix, or `None` if it isn't invertible
+    #[must_use]
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        // Small scales give small determinants, only a zero (or unusable) one can't be inverted
+        if !det.is_normal() {
+            return None;
+        }
+        let m = &self.m;
+        let inv = det.recip();
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            (m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]) * inv
+        };
+        Some(Self {
+            m: [
+                [cofactor(1, 2, 1, 2), -cofactor(0, 2, 1, 2), cofactor(0, 1, 1, 2)],
+                [-cofactor(1, 2, 0, 2), cofactor(0, 2, 0, 2), -cofactor(0, 1, 0, 2)],
+                [cofactor(1, 2, 0, 1), -cofactor(0, 2, 0, 1), cofactor(0, 1, 0, 1)],
+            ],
+        })
+    }
+
+    /// Transform a point, the translation is applied
+    #[must_use]
+    pub fn transform_point(&self, p: Vf2d) -> Vf2d {
+        let m = &self.m;
+        Vec2d {
+            x: m[0][0] * p.x + m[0][1] * p.y + m[0][2],
+            y: m[1][0] * p.x + m[1][1] * p.y + m[1][2],
+        }
+    }
+
+    /// Transform a direction, the translation is ignored
+    #[must_use]
+    pub fn transform_vector(&self, v: Vf2d) -> Vf2d {
+        let m = &self.m;
+        Vec2d {
+            x: m[0][0] * v.x + m[0][1] * v.y,
+            y: m[1][0] * v.x + m[1][1] * v.y,
+        }
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Mat3;
+    fn mul(self, rhs: Mat3) -> Mat3 {
+        let mut m = [[0.0; 3]; 3];
+        for (r, row) in m.iter_mut().enumerate() {
+            for (c, v) in row.iter_mut().enumerate() {
+                *v = (0..3).map(|k| self.m[r][k] * rhs.m[k][c]).sum();
+            }
+        }
+        Mat3 { m }
+    }
+}
+
+impl MulAssign for Mat3 {
+    fn mul_assign(&mut self, rhs: Mat3) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<Vf3d> for Mat3 {
+    type Output = Vf3d;
+    fn mul(self, v: Vf3d) -> Vf3d {
+        let row = |r: [f32; 3]| r[0] * v.x + r[1] * v.y + r[2] * v.z;
+        Vec3d {
+            x: row(self.m[0]),
+            y: row(self.m[1]),
+            z: row(self.m[2]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Vf2d, b: Vf2d) {
+        assert!((a - b).mag_f64() < 1e-4, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn dot_and_cross() {
+        let a = Vi2d::new(2, 3);
+        let b = Vi2d::new(5, -7);
+        assert_eq!(a.dot(&b), 2 * 5 + 3 * -7);
+        assert_eq!(a.cross(&b), 2 * -7 - 3 * 5);
+        assert_eq!(b.cross(&a), -a.cross(&b));
+        // Perpendicular and parallel vectors
+        assert_eq!(Vi2d::new(-3, 2).dot(&a), 0);
+        assert_eq!(a.cross(&(a * 4)), 0);
+        // The cross product is positive when turning from x to y
+        assert_eq!(Vi2d::new(1, 0).cross(&Vi2d::new(0, 1)), 1);
+
+        let a = Vec3d::new(1, 2, 3);
+        let b = Vec3d::new(4, 5, 6);
+        assert_eq!(a.dot(&b), 32);
+        assert_eq!(a.cross(&b), Vec3d::new(-3, 6, -3));
+        assert_eq!(a.cross(&b).dot(&a), 0);
+    }
+
+    #[test]
+    fn inverse_round_trip() {
+        let transforms = [
+            Mat3::IDENTITY,
+            Mat3::translation(Vf2d::new(3.0, -8.0)),
+            Mat3::rotation(0.7),
+            Mat3::scale(Vf2d::new(2.0, 0.5)),
+            Mat3::scale(Vf2d::new(0.001, 0.001)),
+            Mat3::shear(Vf2d::new(0.3, -1.2)),
+            Mat3::translation(Vf2d::new(10.0, 4.0))
+                * Mat3::rotation(-2.1)
+                * Mat3::scale(Vf2d::new(3.0, 0.25)),
+        ];
+        let points = [
+            Vf2d::new(0.0, 0.0),
+            Vf2d::new(1.5, -2.0),
+            Vf2d::new(-40.0, 12.0),
+        ];
+        for m in transforms {
+            let inv = m.inverse().unwrap();
+            for p in points {
+                assert_close(inv.transform_point(m.transform_point(p)), p);
+                assert_close(m.transform_point(inv.transform_point(p)), p);
+            }
+        }
+    }
+
+    #[test]
+    fn singular_inverse() {
+        assert_eq!(Mat3::scale(Vf2d::new(0.0, 1.0)).inverse(), None);
+        assert_eq!(
+            Mat3::new([[1.0, 2.0, 0.0], [2.0, 4.0, 0.0], [0.0, 0.0, 1.0]]).inverse(),
+            None
+        );
+        assert_eq!(Mat3::new([[f32::NAN; 3]; 3]).inverse(), None);
+    }
+}